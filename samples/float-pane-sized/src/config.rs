@@ -0,0 +1,349 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use zellij_tile::prelude::{Key, PaletteColor};
+
+use crate::ui::color::ColorOverrides;
+
+const DEFAULT_ASPECT_RATIO: (u8, u8) = (16, 9);
+const DEFAULT_RESIZE_STEP: u8 = 5;
+const DEFAULT_FINE_STEP: u8 = 1;
+const DEFAULT_COARSE_STEP: u8 = 10;
+const DEFAULT_MIN_PERCENT: u8 = 10;
+
+#[derive(Default, Debug, Clone)]
+pub struct Config {
+    pub default_width: u8,
+    pub default_height: u8,
+    pub presets: Vec<Preset>,
+    // sizes applied to new panes by title, named after the title they apply to
+    pub remembered_sizes: Vec<Preset>,
+    pub aspect_ratio: (u8, u8),
+    pub resize_step: u8,
+    // nudge steps with Alt and with Shift held
+    pub fine_step: u8,
+    pub coarse_step: u8,
+    // 0 leaves typed percentages as they are
+    pub snap_step: u8,
+    // smallest percentage a resize may shrink a pane to
+    pub min_width: u8,
+    pub min_height: u8,
+    // ask before a submitted size is applied
+    pub confirm_resize: bool,
+    // move panes back on screen when a resize makes them overflow
+    pub keep_on_screen: bool,
+    // the corner or the center of a pane that stays in place when it is resized
+    pub anchor: Anchor,
+    // start with resizes only reported on the status line
+    pub dry_run: bool,
+    // hide the plugin once a resize went through
+    pub auto_hide: bool,
+    // move on to the next pane once a resize went through
+    pub advance_after_resize: bool,
+    // keep floating panes proportional to the screen when the terminal is resized
+    pub rescale_on_resize: bool,
+    // the list cursor wraps around at either end, on unless set to false
+    pub nav_wrap: bool,
+    // what leaving the plugin with Esc or Delete does
+    pub on_exit: OnExit,
+    // one resize done without any UI on launch, the plugin is interactive without it
+    pub headless: Option<HeadlessResize>,
+    // title of the floating pane selected as soon as the plugin has loaded
+    pub auto_select: Option<String>,
+    pub keys: KeyBindings,
+    pub colors: ColorOverrides,
+}
+
+// `action "resize"` with the title of the pane and its new percentages
+#[derive(Debug, Clone)]
+pub struct HeadlessResize {
+    pub target: String,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl HeadlessResize {
+    // anything but a complete resize action leaves the plugin interactive
+    fn new(configuration: &BTreeMap<String, String>) -> Option<Self> {
+        if configuration.get("action")?.trim() != "resize" {
+            return None;
+        }
+        let percent = |key: &str| {
+            configuration
+                .get(key)?
+                .trim()
+                .parse::<u16>()
+                .ok()
+                .filter(|percent| (1..=100).contains(percent))
+        };
+        Some(Self {
+            target: configuration.get("target")?.trim().to_string(),
+            width: percent("width")?,
+            height: percent("height")?,
+        })
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum OnExit {
+    // the plugin keeps running in the background and comes back as it was
+    #[default]
+    Hide,
+    Close,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+    // zellij keeps the top left corner where it is by itself
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+impl Anchor {
+    // where a pane at `x`/`y` goes when it changes from the old to the new size, in cells
+    pub fn position(
+        self,
+        (x, y): (usize, usize),
+        (old_cols, old_rows): (usize, usize),
+        (new_cols, new_rows): (usize, usize),
+    ) -> (usize, usize) {
+        let right = (x + old_cols).saturating_sub(new_cols);
+        let bottom = (y + old_rows).saturating_sub(new_rows);
+        match self {
+            Anchor::TopLeft => (x, y),
+            Anchor::TopRight => (right, y),
+            Anchor::BottomLeft => (x, bottom),
+            Anchor::BottomRight => (right, bottom),
+            Anchor::Center => (
+                (x + old_cols / 2).saturating_sub(new_cols / 2),
+                (y + old_rows / 2).saturating_sub(new_rows / 2),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    pub resize: Key,
+    pub reset: Key,
+    pub close: Key,
+    // deselects the pane and never hides the plugin, unlike Esc
+    pub back: Key,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            resize: Key::Ctrl('s'),
+            reset: Key::Ctrl('r'),
+            close: Key::Ctrl('e'),
+            back: Key::Ctrl('u'),
+        }
+    }
+}
+
+impl KeyBindings {
+    // bindings use zellij's own notation, e.g. "Ctrl s" or "Alt x"
+    fn new(configuration: &BTreeMap<String, String>) -> Self {
+        let defaults = Self::default();
+        let parse_key = |name: &str, default: Key| {
+            configuration
+                .get(name)
+                .and_then(|value| Key::from_str(value.trim()).ok())
+                .unwrap_or(default)
+        };
+        Self {
+            resize: parse_key("key_resize", defaults.resize),
+            reset: parse_key("key_reset", defaults.reset),
+            close: parse_key("key_close", defaults.close),
+            back: parse_key("key_back", defaults.back),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Preset {
+    pub name: String,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Config {
+    pub fn new(configuration: &BTreeMap<String, String>) -> Self {
+        Self {
+            default_width: parse_percent(configuration, "default_width"),
+            default_height: parse_percent(configuration, "default_height"),
+            presets: configuration
+                .get("presets")
+                .map(|value| parse_presets(value))
+                .unwrap_or_default(),
+            remembered_sizes: configuration
+                .get("remembered_sizes")
+                .map(|value| parse_remembered_sizes(value))
+                .unwrap_or_default(),
+            aspect_ratio: configuration
+                .get("aspect_ratio")
+                .and_then(|value| parse_ratio(value))
+                .unwrap_or(DEFAULT_ASPECT_RATIO),
+            resize_step: parse_step(configuration, "resize_step", DEFAULT_RESIZE_STEP),
+            fine_step: parse_step(configuration, "fine_step", DEFAULT_FINE_STEP),
+            coarse_step: parse_step(configuration, "coarse_step", DEFAULT_COARSE_STEP),
+            snap_step: configuration
+                .get("snap_step")
+                .and_then(|value| value.trim().parse::<u8>().ok())
+                .unwrap_or(0),
+            min_width: parse_min_percent(configuration, "min_width"),
+            min_height: parse_min_percent(configuration, "min_height"),
+            confirm_resize: parse_flag(configuration, "confirm_resize"),
+            keep_on_screen: parse_flag(configuration, "keep_on_screen"),
+            anchor: match configuration.get("anchor").map(|value| value.trim()) {
+                Some("top_right") => Anchor::TopRight,
+                Some("bottom_left") => Anchor::BottomLeft,
+                Some("bottom_right") => Anchor::BottomRight,
+                Some("center") => Anchor::Center,
+                _ => Anchor::TopLeft,
+            },
+            dry_run: parse_flag(configuration, "dry_run"),
+            auto_hide: parse_flag(configuration, "auto_hide"),
+            advance_after_resize: parse_flag(configuration, "advance_after_resize"),
+            rescale_on_resize: parse_flag(configuration, "rescale_on_resize"),
+            nav_wrap: configuration
+                .get("nav_wrap")
+                .is_none_or(|value| value.trim() != "false"),
+            on_exit: match configuration.get("on_exit").map(|value| value.trim()) {
+                Some("close") => OnExit::Close,
+                _ => OnExit::Hide,
+            },
+            headless: HeadlessResize::new(configuration),
+            auto_select: configuration
+                .get("auto_select")
+                .map(|title| title.trim().to_string())
+                .filter(|title| !title.is_empty()),
+            keys: KeyBindings::new(configuration),
+            colors: ColorOverrides {
+                selected: parse_color(configuration.get("color_selected")),
+                cursor: parse_color(configuration.get("color_cursor")),
+                border: parse_color(configuration.get("color_border")),
+                text: parse_color(configuration.get("color_text")),
+            },
+        }
+    }
+}
+
+// missing or invalid values, out of range ones included, fall back to 0, same as an empty input
+fn parse_percent(configuration: &BTreeMap<String, String>, key: &str) -> u8 {
+    configuration
+        .get(key)
+        .and_then(|value| value.trim().parse::<u8>().ok())
+        .filter(|percent| (1..=100).contains(percent))
+        .unwrap_or(0)
+}
+
+// only an explicit "true" turns a flag on
+fn parse_flag(configuration: &BTreeMap<String, String>, key: &str) -> bool {
+    configuration
+        .get(key)
+        .is_some_and(|value| value.trim() == "true")
+}
+
+fn parse_step(configuration: &BTreeMap<String, String>, key: &str, default: u8) -> u8 {
+    configuration
+        .get(key)
+        .and_then(|value| value.trim().parse::<u8>().ok())
+        .filter(|&step| step > 0)
+        .unwrap_or(default)
+}
+
+fn parse_min_percent(configuration: &BTreeMap<String, String>, key: &str) -> u8 {
+    configuration
+        .get(key)
+        .and_then(|value| value.trim().parse::<u8>().ok())
+        .filter(|&percent| percent <= 100)
+        .unwrap_or(DEFAULT_MIN_PERCENT)
+}
+
+// presets are written as "small=30x20, medium=50x40", entries that don't parse are skipped
+fn parse_presets(value: &str) -> Vec<Preset> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|entry| {
+            let (name, size) = entry.split_once('=')?;
+            let (width, height) = size.split_once('x')?;
+            Some(Preset {
+                name: name.to_string(),
+                width: width.parse().ok()?,
+                height: height.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// written as "htop=60x40, my notes=80x70", titles may contain spaces but no commas and the
+// last '=' starts the size
+fn parse_remembered_sizes(value: &str) -> Vec<Preset> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (title, size) = entry.trim().rsplit_once('=')?;
+            let (width, height) = size.split_once('x')?;
+            Some(Preset {
+                name: title.trim().to_string(),
+                width: width.trim().parse().ok()?,
+                height: height.trim().parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// ratios are written as "16:9", a zero width can't be used to derive a height
+fn parse_ratio(value: &str) -> Option<(u8, u8)> {
+    let (width, height) = value.trim().split_once(':')?;
+    let width = width.parse::<u8>().ok().filter(|&width| width > 0)?;
+    Some((width, height.parse().ok()?))
+}
+
+// accepts a color name, an 8-bit palette index or a "#rrggbb" hex value
+fn parse_color(value: Option<&String>) -> Option<PaletteColor> {
+    let value = value?.trim().to_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(PaletteColor::Rgb((channel(0)?, channel(2)?, channel(4)?)));
+    }
+    let byte = match value.as_str() {
+        "black" => 0,
+        "red" => 1,
+        "green" => 2,
+        "yellow" => 3,
+        "blue" => 4,
+        "magenta" => 5,
+        "cyan" => 6,
+        "white" => 7,
+        "gray" | "grey" => 8,
+        "orange" => 208,
+        "pink" => 213,
+        other => other.parse().ok()?,
+    };
+    Some(PaletteColor::EightBit(byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_sizes_out_of_range_fall_back_to_0() {
+        let configuration = BTreeMap::from([
+            ("default_width".to_string(), "250".to_string()),
+            ("default_height".to_string(), " 40 ".to_string()),
+        ]);
+        let config = Config::new(&configuration);
+        assert_eq!((config.default_width, config.default_height), (0, 40));
+    }
+}
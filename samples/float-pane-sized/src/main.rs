@@ -1,13 +1,172 @@
+mod config;
 mod ui;
 
+use config::{Config, HeadlessResize, OnExit, Preset};
 use ui::color::Colors;
 use ui::panes::PaneUi;
-use ui::widgets::compose_ui;
+use ui::widgets::{
+    compose_ui, list_capacity, list_first_row, pane_at, pane_beside, scroll_offset, Footer,
+    ListArea, PaneList, SessionPicker, SizeInput,
+};
 
 use zellij_tile::prelude::*;
 
 use nohash_hasher::IntMap;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+const MIN_PERCENT: u16 = 1;
+const MAX_PERCENT: u16 = 100;
+const MIN_CELLS: u16 = 1;
+// how much of a moved pane has to stay on screen, in cells
+const MIN_VISIBLE_CELLS: usize = 5;
+const MAX_INPUT_LEN: usize = 3;
+const MAXIMIZED_PERCENT: u16 = 90;
+// restore target for panes that were already maximized-sized before the toggle
+const DEFAULT_RESTORE_PERCENT: u16 = 50;
+const MAX_HISTORY_LEN: usize = 10;
+const HALF_PERCENT: u16 = 50;
+const GOLDEN_RATIO: f64 = 1.618;
+// the width most terminal programs format their output for
+const CONTENT_COLUMNS: usize = 80;
+// roughly how much zellij grows or shrinks a tiled pane per resize step
+const TILED_RESIZE_STEP_PERCENT: u16 = 5;
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+const SPINNER_INTERVAL_SECS: f64 = 0.1;
+// long enough to read a message, and well apart from the spinner interval so their timer
+// events can be told apart
+const STATUS_TIMEOUT_SECS: f64 = 4.0;
+// zellij starts timing a timeout a little after it was set, so it can end that much early
+const TIMER_SLACK: Duration = Duration::from_millis(100);
+// how often the "resized ... ago" labels are redrawn, they only count minutes
+const AGE_TICK_SECS: f64 = 30.0;
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Default, Clone, Copy, PartialEq)]
+enum ResizeMode {
+    #[default]
+    Percent,
+    Cells,
+}
+
+impl ResizeMode {
+    fn toggle(self) -> Self {
+        match self {
+            ResizeMode::Percent => ResizeMode::Cells,
+            ResizeMode::Cells => ResizeMode::Percent,
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            ResizeMode::Percent => "percent",
+            ResizeMode::Cells => "cells",
+        }
+    }
+}
+
+// what the typed numbers and the submit key do to the selected pane
+#[derive(Default, Clone, Copy, PartialEq)]
+enum InteractionMode {
+    #[default]
+    Resize,
+    Move,
+}
+
+impl InteractionMode {
+    fn toggle(self) -> Self {
+        match self {
+            InteractionMode::Resize => InteractionMode::Move,
+            InteractionMode::Move => InteractionMode::Resize,
+        }
+    }
+}
+
+// which tabs the listed panes come from
+#[derive(Default, Clone, Copy, PartialEq)]
+enum Scope {
+    #[default]
+    AllTabs,
+    CurrentTab,
+}
+
+// which side a typed value resizes, the other one keeps its current size
+#[derive(Default, Clone, Copy, PartialEq)]
+enum Axis {
+    #[default]
+    Both,
+    Width,
+    Height,
+}
+
+impl Axis {
+    // pressing the key of the axis already chosen goes back to both
+    fn toggle(self, axis: Axis) -> Self {
+        if self == axis {
+            Axis::Both
+        } else {
+            axis
+        }
+    }
+
+    fn name(self) -> Option<&'static str> {
+        match self {
+            Axis::Both => None,
+            Axis::Width => Some("width"),
+            Axis::Height => Some("length"),
+        }
+    }
+}
+
+// order of the panes within each tab of the list
+#[derive(Default, Clone, Copy, PartialEq)]
+enum SortMode {
+    #[default]
+    Index,
+    Title,
+    // largest first
+    Size,
+}
+
+impl SortMode {
+    fn next(self) -> Self {
+        match self {
+            SortMode::Index => SortMode::Title,
+            SortMode::Title => SortMode::Size,
+            SortMode::Size => SortMode::Index,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SortMode::Index => "index",
+            SortMode::Title => "title",
+            SortMode::Size => "size",
+        }
+    }
+}
+
+// what an undo puts back
+#[derive(Clone)]
+enum Undo {
+    // percentages of the selected pane
+    Size(u16, u16),
+    // every pane an arrangement moved, as it was before
+    Layout(Vec<PaneUi>),
+}
+
+// what pressing Enter did with the typed input
+#[derive(Clone, Copy, PartialEq)]
+enum Commit {
+    SelectedPane,
+    X,
+    Position,
+    Width,
+    // both sides are known, typed one after the other or the second derived from the first
+    Size,
+    Nothing,
+}
 
 #[derive(Default, Clone)]
 struct State {
@@ -16,16 +175,121 @@ struct State {
     selected_pane: Option<PaneUi>,
     cursor_pane_index: Option<usize>,
     colors: Colors,
-    new_width: u8,
-    new_height: u8,
+    new_width: u16,
+    new_height: u16,
     input_buffer: String,
     awaiting_length_input: bool,
+    resize_mode: ResizeMode,
+    axis: Axis,
+    mode: InteractionMode,
+    new_x: u16,
+    new_y: u16,
+    screen_rows: usize,
+    screen_cols: usize,
+    waiting_for_session: bool,
+    permission_denied: bool,
+    // size to restore to while the selected pane is maximized
+    maximized_from: Option<(u16, u16)>,
+    // width:height ratio used to derive the height from a committed width
+    aspect_lock: Option<(u8, u8)>,
+    // sizes of the selected pane before each resize and arrangements of all panes, most recent
+    // last; the sizes go when the pane is deselected
+    resize_history: Vec<Undo>,
+    // apply the next resize to every floating pane instead of only the selected one, or to
+    // the marked ones when there are any
+    select_all: bool,
+    // panes picked for a bulk resize, by id like the pins
+    marked: HashSet<PaneId>,
+    // pane waiting for a second Ctrl+D to be closed
+    pending_close: Option<PaneUi>,
+    // submitted size waiting for a yes when `confirm_resize` is configured
+    confirming_resize: bool,
+    // columns and rows of the selected pane when a resize was sent, until an update shows it
+    // at another size
+    resize_in_flight: Option<(usize, usize)>,
+    // pane asked to be embedded, checked against the next session update
+    pending_embed: Option<PaneId>,
+    // resizes only say what they would do, starts out as configured
+    dry_run: bool,
+    // digits of the index typed with Alt so far, so Alt-1 Alt-2 reaches pane 12
+    jump_digits: String,
+    // hide the plugin after a resize went through, starts out as configured
+    auto_hide: bool,
+    // go back to the list with the next pane highlighted after a resize went through
+    advance_after_resize: bool,
+    // sizes new panes get by title, the first entry for a title wins
+    remembered_sizes: Vec<Preset>,
+    // floating panes of the current session as of the last update, to tell new ones apart;
+    // unset until the first update so the panes already open are left alone
+    seen_panes: Option<HashSet<PaneId>>,
+    // case-insensitive title filter, `filtering` while the user is still typing it
+    filter: String,
+    filtering: bool,
+    sort_mode: SortMode,
+    // panes listed ahead of all others, by id so they stay pinned across session updates
+    pinned: HashSet<PaneId>,
+    show_help: bool,
+    show_minimap: bool,
+    // a bar on every row showing how much of the screen width the pane takes
+    show_size_bars: bool,
+    // the raw ids zellij knows the selected pane by, for scripts and bug reports
+    show_debug: bool,
+    // pane picked to copy its size from, the next pane picked in the list gets that size
+    copy_source: Option<PaneUi>,
+    // list tiled panes next to the floating ones
+    show_tiled: bool,
+    scope: Scope,
+    // last session update, to rebuild the list when what it shows changes
+    sessions: Vec<SessionInfo>,
+    // another session whose panes are browsed instead of the current one's, read only since
+    // the resize, move and close commands always act on the current session
+    browsed_session: Option<String>,
+    // cursor into `sessions` while the user picks a session to browse
+    session_picker: Option<usize>,
+    // how many list rows fit on screen as of the last render, one page for PageUp/PageDown
+    page_rows: usize,
+    // first list line on screen, kept so the list only scrolls when the cursor leaves it
+    scroll_offset: usize,
+    // columns of the plugin as of the last render, the list spreads over several when wide
+    list_cols: usize,
+    // pane under the cursor when the plugin was hidden and whether it was selected,
+    // picked up again by the first session update after that
+    restore_pane: Option<(PaneId, bool)>,
+    // pane index and time of the last click on the list, to tell double clicks apart
+    last_click: Option<(usize, Instant)>,
+    // pane index, line and column a press on the list started on, a release elsewhere
+    // resizes that pane by the distance dragged
+    drag_origin: Option<(usize, isize, usize)>,
+    // advanced on every timer tick while loading
+    spinner_frame: usize,
+    timer_running: bool,
+    // configured resize still to be done on the first session update
+    headless: Option<HeadlessResize>,
+    // configured title of the pane to select on the first session update
+    auto_select: Option<String>,
+    // feedback for the last key press, cleared by the next one or once it timed out
+    status_message: Option<String>,
+    // the message the timeout below was started for
+    status_shown: Option<String>,
+    status_expires: Option<Instant>,
+    // when panes of the current session were last resized through the plugin
+    resized_at: HashMap<PaneId, Instant>,
+    age_tick_running: bool,
+    config: Config,
 }
 
 register_plugin!(State);
 
 impl ZellijPlugin for State {
-    fn load(&mut self, _configuration: BTreeMap<String, String>) {
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        self.config = Config::new(&configuration);
+        self.colors = Colors::default().with_overrides(self.config.colors);
+        self.dry_run = self.config.dry_run;
+        self.auto_hide = self.config.auto_hide;
+        self.advance_after_resize = self.config.advance_after_resize;
+        self.remembered_sizes = self.config.remembered_sizes.clone();
+        self.headless = self.config.headless.clone();
+        self.auto_select = self.config.auto_select.clone();
         request_permission(&[
             PermissionType::ReadApplicationState,
             PermissionType::ChangeApplicationState,
@@ -34,91 +298,543 @@ impl ZellijPlugin for State {
             EventType::SessionUpdate,
             EventType::Key,
             EventType::ModeUpdate,
+            EventType::Mouse,
+            EventType::Timer,
         ]);
         self.is_loading = true;
+        self.start_timer();
     }
 
     fn update(&mut self, event: Event) -> bool {
         let mut render = false;
         match event {
-            Event::ModeUpdate(mode_info) => {
-                self.colors = Colors::new(mode_info.style.colors);
+            // mode updates come with every mode switch, most of them leave the palette as it was
+            Event::ModeUpdate(mode_info) if mode_info.style.colors != self.colors.palette => {
+                self.colors =
+                    Colors::new(mode_info.style.colors).with_overrides(self.config.colors);
                 render = true;
             }
+            Event::ModeUpdate(_) => {}
             Event::Key(key) => {
                 self.handle_key(key);
                 render = true;
             }
+            Event::Mouse(mouse) => {
+                self.handle_mouse(mouse);
+                render = true;
+            }
             Event::SessionUpdate(session_info) => {
                 self.get_panes(&session_info);
+                if let Some(resize) = self.headless.take() {
+                    self.run_headless(&resize);
+                }
+                if let Some(title) = self.auto_select.take() {
+                    self.select_by_title(&title);
+                }
                 if self.selected_pane.is_some() {
                     self.update_selected_pane(&session_info);
                 }
+                self.sessions = session_info;
                 self.is_loading = false;
                 render = true;
             }
-            Event::PermissionRequestResult(_result) => {
+            // zellij reports how long the timer ran, which tells the spinner's from a status
+            // timeout and from the age tick; the timeouts of replaced messages fire early and
+            // leave the current message to a timeout for the rest of its time
+            Event::Timer(elapsed) => {
+                if elapsed < STATUS_TIMEOUT_SECS {
+                    self.timer_running = false;
+                    if self.is_loading {
+                        self.spinner_frame = self.spinner_frame.wrapping_add(1);
+                        self.start_timer();
+                        render = true;
+                    }
+                }
+                if elapsed >= AGE_TICK_SECS {
+                    self.age_tick_running = false;
+                    if !self.resized_at.is_empty() {
+                        self.start_age_tick();
+                        render = true;
+                    }
+                }
+                match self.status_expires {
+                    Some(at) if Instant::now() + TIMER_SLACK >= at => {
+                        self.status_message = None;
+                        self.status_expires = None;
+                        render = true;
+                    }
+                    Some(at) => set_timeout((at - Instant::now()).as_secs_f64()),
+                    None => {}
+                }
+            }
+            Event::PermissionRequestResult(result) => {
+                self.permission_denied = result == PermissionStatus::Denied;
                 render = true;
             }
             _ => {
                 self.is_loading = true;
+                self.start_timer();
             }
         }
+        self.track_status();
         render
     }
 
     fn render(&mut self, rows: usize, cols: usize) {
+        if self.permission_denied {
+            println!("This plugin needs permission to read and change the application state");
+            println!("to list and resize floating panes, but it was denied.");
+            println!("Relaunch the plugin and grant the permissions when prompted.");
+            let leave = match self.config.on_exit {
+                OnExit::Hide => "hide",
+                OnExit::Close => "close",
+            };
+            println!("Press <ESC> to {leave} this plugin.");
+            return;
+        }
+        if self.waiting_for_session {
+            println!("Waiting for session...");
+            return;
+        }
+        if let Some(resize) = &self.headless {
+            println!("Resizing {}...", resize.target);
+            return;
+        }
         if !self.is_loading {
-            let panes: Vec<PaneUi> = self.panes.values().cloned().collect();
+            self.page_rows = list_capacity(rows, self.first_list_row());
+            self.list_cols = cols;
+            let panes = self.listed_panes();
+            self.scroll_offset = scroll_offset(&panes, self.cursor_pane_index, self.list_area());
             compose_ui(
                 rows,
                 cols,
                 self.colors,
-                panes,
-                self.selected_pane.as_ref(),
-                self.cursor_pane_index,
-                self.new_width,
-                self.new_height,
+                PaneList {
+                    panes,
+                    selected_pane: self.selected_pane.as_ref(),
+                    current_pane_index: self.cursor_pane_index,
+                    pending_close: self.pending_close.as_ref(),
+                    filter: &self.filter,
+                    filtering: self.filtering,
+                    minimap: self.minimap_screen(),
+                    size_bars: (self.show_size_bars && self.screen_cols > 0)
+                        .then_some(self.screen_cols),
+                    browsed_session: self.browsed_session.as_deref(),
+                    area: self.list_area(),
+                    debug: self.show_debug,
+                    copy_source: self.copy_source.as_ref(),
+                    current_tab_only: self.scope == Scope::CurrentTab,
+                    marked: self.marked_count(),
+                    session_picker: self.session_picker.map(|cursor| SessionPicker {
+                        sessions: self
+                            .sessions
+                            .iter()
+                            .map(|session| (session.name.as_str(), session.is_current_session))
+                            .collect(),
+                        cursor,
+                    }),
+                },
+                SizeInput {
+                    new_width: self.new_width,
+                    new_height: self.new_height,
+                    input_buffer: &self.input_buffer,
+                    awaiting_length_input: self.awaiting_length_input,
+                    resize_mode: self.resize_mode,
+                    moving: self.mode == InteractionMode::Move,
+                    new_x: self.new_x,
+                    new_y: self.new_y,
+                    presets: &self.config.presets,
+                    aspect_lock: self.aspect_lock,
+                    select_all: self.select_all,
+                    axis: self.axis,
+                    confirming: self.confirming_resize,
+                    resizing: self.resize_in_flight.is_some(),
+                    screen: (self.screen_cols, self.screen_rows),
+                },
+                Footer {
+                    show_help: self.show_help,
+                    status_message: self.status_message.as_deref(),
+                    keys: &self.config.keys,
+                    dry_run: self.dry_run,
+                    on_exit: self.config.on_exit,
+                },
             );
+        } else {
+            let frame = SPINNER_FRAMES[self.spinner_frame % SPINNER_FRAMES.len()];
+            println!("{frame} Loading panes...");
         }
     }
 }
 
 impl State {
+    // a single timeout is kept in flight so ticks don't pile up
+    // every new message gets the full timeout, however it was set
+    fn track_status(&mut self) {
+        if self.status_message == self.status_shown {
+            return;
+        }
+        self.status_shown = self.status_message.clone();
+        self.status_expires = self.status_message.as_ref().map(|_| {
+            let at = Instant::now() + Duration::from_secs_f64(STATUS_TIMEOUT_SECS);
+            set_timeout(STATUS_TIMEOUT_SECS);
+            at
+        });
+    }
+
+    // the labels only change once a minute, so one slow tick is enough for all of them
+    fn start_age_tick(&mut self) {
+        if !self.age_tick_running {
+            set_timeout(AGE_TICK_SECS);
+            self.age_tick_running = true;
+        }
+    }
+
+    fn mark_resized(&mut self, id: PaneId) {
+        self.resized_at.insert(id, Instant::now());
+        self.start_age_tick();
+    }
+
+    fn start_timer(&mut self) {
+        if !self.timer_running {
+            set_timeout(SPINNER_INTERVAL_SECS);
+            self.timer_running = true;
+        }
+    }
+
     fn get_panes(&mut self, session: &[SessionInfo]) {
-        self.panes.clear();
-        let current_session = session
-            .iter()
-            .find(|session| session.is_current_session)
-            .expect("no current session");
-        let mut start_idx = 1;
+        let browsed = self.browsed_session.as_deref().and_then(|name| {
+            session
+                .iter()
+                .find(|session| session.name == name && !session.is_current_session)
+        });
+        // the browsed session went away or we were switched into it
+        if browsed.is_none() && self.browsed_session.is_some() {
+            self.browsed_session = None;
+            self.panes.clear();
+        }
+        // a transient update can come without a current session, keep the last known panes until the next one
+        let Some(current_session) =
+            browsed.or_else(|| session.iter().find(|session| session.is_current_session))
+        else {
+            self.waiting_for_session = true;
+            return;
+        };
+        self.waiting_for_session = false;
+        let old_screen = (self.screen_cols, self.screen_rows);
+        self.update_screen_size(current_session);
+        if browsed.is_none() {
+            // panes of all tabs count, whatever the list is scoped to
+            let open: HashSet<PaneId> = current_session
+                .panes
+                .panes
+                .values()
+                .flatten()
+                .map(PaneUi::id_of)
+                .collect();
+            self.resized_at.retain(|id, _| open.contains(id));
+            if self.config.rescale_on_resize && old_screen != (self.screen_cols, self.screen_rows) {
+                self.rescale_panes(current_session, old_screen);
+            }
+            self.apply_remembered_sizes(current_session);
+        }
 
-        for tab in &current_session.tabs {
+        let mut floating_panes: Vec<PaneUi> = Vec::new();
+        let in_scope = |tab: &&TabInfo| self.scope == Scope::AllTabs || tab.active;
+        for tab in current_session.tabs.iter().filter(in_scope) {
             if let Some(related_panes) = current_session.panes.panes.get(&tab.position) {
-                let filtered_panes: Vec<PaneUi> = related_panes
-                    .iter()
-                    .filter_map(|pane| {
-                        if pane.is_floating {
-                            Some(PaneUi::new(pane, tab))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
+                floating_panes.extend(
+                    related_panes
+                        .iter()
+                        .filter(|pane| {
+                            pane.is_floating
+                                || (self.show_tiled && pane.is_selectable && !pane.is_suppressed)
+                        })
+                        .map(|pane| PaneUi::new(pane, tab)),
+                );
+            }
+        }
+
+        // panes keep their index for as long as they exist so the cursor doesn't jump around,
+        // new ones are appended after the highest index in use
+        self.panes.retain(|_, pane| {
+            floating_panes
+                .iter()
+                .any(|floating| floating.id() == pane.id())
+        });
+        let mut next_idx = self.panes.keys().max().map_or(1, |idx| idx + 1);
+        for pane in floating_panes {
+            if let Some(existing) = self
+                .panes
+                .values_mut()
+                .find(|existing| existing.id() == pane.id())
+            {
+                *existing = pane;
+            } else {
+                self.panes.insert(next_idx, pane);
+                next_idx += 1;
+            }
+        }
+        self.clamp_cursor();
+        self.restore_last_pane();
+        self.check_embed(current_session);
+    }
+
+    // gives every tracked floating pane the share of the new screen it had of the old one, going
+    // by the geometry of the last update since zellij may already have moved it
+    fn rescale_panes(&mut self, session: &SessionInfo, (old_cols, old_rows): (usize, usize)) {
+        let (new_cols, new_rows) = (self.screen_cols, self.screen_rows);
+        if old_cols == 0 || old_rows == 0 || new_cols == 0 || new_rows == 0 {
+            return;
+        }
+        let still_floating = |pane: &PaneUi| {
+            session
+                .panes
+                .panes
+                .values()
+                .flatten()
+                .any(|info| info.is_floating && PaneUi::id_of(info) == pane.id())
+        };
+        let mut rescaled = 0;
+        for pane in self
+            .panes
+            .values()
+            .filter(|pane| pane.is_floating && still_floating(pane))
+        {
+            let width = cells_to_percent(pane.pane_columns, old_cols);
+            let height = cells_to_percent(pane.pane_rows, old_rows);
+            let x = pane.pane_x * new_cols / old_cols;
+            let y = pane.pane_y * new_rows / old_rows;
+            if self.dry_run {
+                rescaled += 1;
+                continue;
+            }
+            let result =
+                resize_pane_by_percent(pane, width, height).and_then(|()| move_pane(pane, x, y));
+            match result {
+                Ok(()) => rescaled += 1,
+                Err(message) => self.status_message = Some(message),
+            }
+        }
+        if rescaled > 0 && self.status_message.is_none() {
+            let verb = if self.dry_run {
+                "Dry run: would rescale"
+            } else {
+                "Rescaled"
+            };
+            self.status_message = Some(format!(
+                "{verb} {rescaled} pane(s) from {old_cols}x{old_rows} to {new_cols}x{new_rows}"
+            ));
+        }
+    }
+
+    // floating panes that showed up since the last update get the size remembered for their title
+    fn apply_remembered_sizes(&mut self, session: &SessionInfo) {
+        let mut floating_panes = Vec::new();
+        for tab in &session.tabs {
+            if let Some(related_panes) = session.panes.panes.get(&tab.position) {
+                floating_panes.extend(
+                    related_panes
+                        .iter()
+                        .filter(|pane| pane.is_floating)
+                        .map(|pane| PaneUi::new(pane, tab)),
+                );
+            }
+        }
+        let ids = floating_panes.iter().map(PaneUi::id).collect();
+        let Some(seen) = self.seen_panes.replace(ids) else {
+            return;
+        };
+        for pane in floating_panes
+            .iter()
+            .filter(|pane| !seen.contains(&pane.id()))
+        {
+            let Some(size) = self
+                .remembered_sizes
+                .iter()
+                .find(|remembered| remembered.name == pane.name)
+            else {
+                continue;
+            };
+            let (width, height) = (size.width, size.height);
+            if self.dry_run {
+                self.status_message = Some(format!(
+                    "Dry run: would resize new pane {} to its remembered {width}x{height} percent",
+                    pane.name
+                ));
+            } else if let Err(message) = resize_pane_by_percent(pane, width, height) {
+                self.status_message = Some(message);
+            }
+        }
+    }
+
+    // keeps the selected pane's size for its title, replacing what was remembered for it before
+    fn remember_size(&mut self) {
+        let Some((width, height)) = self.current_size_percent() else {
+            self.status_message =
+                Some("Screen size unknown, can't remember the size yet".to_string());
+            return;
+        };
+        let title = self.selected_pane.as_ref().unwrap().name.clone();
+        self.remembered_sizes
+            .retain(|remembered| remembered.name != title);
+        self.status_message = Some(format!(
+            "Remembered {width}x{height} percent for panes titled {title}"
+        ));
+        self.remembered_sizes.push(Preset {
+            name: title,
+            width,
+            height,
+        });
+    }
+
+    // resizes the first floating pane with the configured title and gets out of the way; when
+    // there is none the plugin stays up to say so and works as usual from there
+    fn run_headless(&mut self, resize: &HeadlessResize) {
+        let Some(pane) = self
+            .panes
+            .values()
+            .find(|pane| pane.is_floating && pane.name == resize.target)
+        else {
+            self.status_message = Some(format!("No floating pane titled {}", resize.target));
+            return;
+        };
+        let (width, height) = (resize.width, resize.height);
+        if self.dry_run {
+            self.status_message = Some(format!(
+                "Dry run: would resize {} to {width}x{height} percent",
+                resize.target
+            ));
+            return;
+        }
+        match resize_pane_by_percent(pane, width, height) {
+            Ok(()) => hide_self(),
+            Err(message) => self.status_message = Some(message),
+        }
+    }
+
+    // selects the first floating pane with the title straight away, without one the list is
+    // shown as usual
+    fn select_by_title(&mut self, title: &str) {
+        let Some(idx) = self
+            .panes
+            .iter()
+            .filter(|(_, pane)| pane.is_floating && pane.name == title)
+            .map(|(idx, _)| *idx)
+            .min()
+        else {
+            self.status_message = Some(format!("No floating pane titled {title}"));
+            return;
+        };
+        self.cursor_pane_index = Some(idx);
+        self.select_cursor_pane();
+    }
+
+    // rebuilds the list from the last session update after what it shows changed
+    fn reload_panes(&mut self) {
+        let sessions = std::mem::take(&mut self.sessions);
+        self.get_panes(&sessions);
+        self.sessions = sessions;
+    }
+
+    // true, with the reason on the status line, if the panes belong to another session
+    fn browse_only(&mut self) -> bool {
+        if self.browsed_session.is_some() {
+            self.status_message =
+                Some("Panes of other sessions can only be browsed and focused".to_string());
+        }
+        self.browsed_session.is_some()
+    }
+
+    fn handle_session_picker_key(&mut self, key: Key, cursor: usize) {
+        let count = self.sessions.len();
+        match key {
+            Key::Down | Key::Char('j') if count > 0 => {
+                self.session_picker = Some((cursor + 1) % count);
+            }
+            Key::Up | Key::Char('k') if count > 0 => {
+                self.session_picker = Some((cursor + count - 1) % count);
+            }
+            Key::Char('\n') => {
+                self.session_picker = None;
+                let Some(session) = self.sessions.get(cursor) else {
+                    return;
+                };
+                self.browsed_session = (!session.is_current_session).then(|| session.name.clone());
+                // pane ids are only unique within a session
+                self.deselect_pane();
+                self.panes.clear();
+                self.cursor_pane_index = None;
+                self.reload_panes();
+            }
+            Key::Esc => self.session_picker = None,
+            _ => {}
+        }
+    }
+
+    // the minimap can only be scaled once the screen size is known
+    fn minimap_screen(&self) -> Option<(usize, usize)> {
+        (self.show_minimap && self.screen_cols > 0 && self.screen_rows > 0)
+            .then_some((self.screen_cols, self.screen_rows))
+    }
 
-                for pane in filtered_panes {
-                    self.panes.insert(start_idx, pane);
-                    start_idx += 1;
+    fn restore_last_pane(&mut self) {
+        let Some((id, was_selected)) = self.restore_pane.take() else {
+            return;
+        };
+        match self.panes.iter().find(|(_, pane)| pane.id() == id) {
+            Some((&idx, _)) => {
+                self.cursor_pane_index = Some(idx);
+                if was_selected {
+                    self.select_cursor_pane();
                 }
             }
+            None => {
+                self.deselect_pane();
+                self.cursor_pane_index = self.visible_indices().first().copied();
+            }
+        }
+    }
+
+    // remembers where the user was so showing the plugin again brings them back there
+    fn hide(&mut self) {
+        let cursor_pane = self.cursor_pane_index.and_then(|idx| self.panes.get(&idx));
+        self.restore_pane = match (&self.selected_pane, cursor_pane) {
+            (Some(pane), _) => Some((pane.id(), true)),
+            (None, Some(pane)) => Some((pane.id(), false)),
+            (None, None) => None,
+        };
+        hide_self();
+    }
+
+    fn exit(&mut self) {
+        match self.config.on_exit {
+            OnExit::Hide => self.hide(),
+            OnExit::Close => close_plugin_pane(get_plugin_ids().plugin_id),
+        }
+    }
+
+    // floating panes are laid over the tiled ones, so the tiled panes span the whole display area
+    fn update_screen_size(&mut self, session: &SessionInfo) {
+        let tiled_panes = session
+            .panes
+            .panes
+            .values()
+            .flatten()
+            .filter(|pane| !pane.is_floating && !pane.is_suppressed);
+
+        self.screen_cols = 0;
+        self.screen_rows = 0;
+        for pane in tiled_panes {
+            self.screen_cols = self.screen_cols.max(pane.pane_x + pane.pane_columns);
+            self.screen_rows = self.screen_rows.max(pane.pane_y + pane.pane_rows);
         }
     }
 
     fn update_selected_pane(&mut self, session: &[SessionInfo]) {
-        let current_session = session
-            .iter()
-            .find(|session| session.is_current_session)
-            .expect("no current session");
+        let Some(current_session) = session.iter().find(|session| session.is_current_session)
+        else {
+            return;
+        };
 
         if let Some(selected_pane) = &self.selected_pane {
             let selected_tab_id = selected_pane.parent_tab.tab_id;
@@ -138,116 +854,2213 @@ impl State {
                 if let Some(parent_tab) = current_session.tabs.get(selected_tab_id) {
                     self.selected_pane = Some(PaneUi::new(pane_info, parent_tab));
                 }
+                if self.resize_in_flight != Some((pane_info.pane_columns, pane_info.pane_rows)) {
+                    self.resize_in_flight = None;
+                }
             }
         }
     }
 
-    fn send_resize_event(&mut self) {
-        let size = ResizeByPercent {
-            width: self.new_width as u32,
-            height: self.new_height as u32,
+    // false when not every pane was resized, a dry run included, the status line then says why
+    fn send_resize_event(&mut self) -> bool {
+        let (min_width, min_height) = self.min_size();
+        let (width, height) = match self.resize_mode {
+            ResizeMode::Percent => (
+                self.new_width.clamp(min_width, MAX_PERCENT),
+                self.new_height.clamp(min_height, MAX_PERCENT),
+            ),
+            ResizeMode::Cells => (
+                self.new_width.max(min_width),
+                self.new_height.max(min_height),
+            ),
         };
-
-        let tab_pos = self.selected_pane.as_ref().unwrap().parent_tab.tab_id;
-        let pane_id = if let Some(pane) = self.selected_pane.as_ref() {
-            if pane.is_plugin {
-                Some(PaneId::Plugin(pane.pane_id))
+        let unit = self.resize_mode.unit();
+        if self.dry_run {
+            let target = if self.select_all {
+                self.bulk_target()
             } else {
-                Some(PaneId::Terminal(pane.pane_id))
-            }
+                let pane = self.selected_pane.as_ref().unwrap();
+                format!("pane {} in tab {}", pane.pane_id, pane.parent_tab.tab_id)
+            };
+            self.status_message = Some(format!(
+                "Dry run: would resize {target} to {width}x{height} {unit}"
+            ));
+            self.new_width = 0;
+            self.new_height = 0;
+            return false;
+        }
+        let target = if self.select_all {
+            self.bulk_target()
+        } else {
+            format!("pane {}", self.selected_pane.as_ref().unwrap().pane_id)
+        };
+        self.status_message = Some(if width > self.new_width || height > self.new_height {
+            format!("Below the minimum size, resized {target} to {width}x{height} {unit} instead")
+        } else if width != self.new_width || height != self.new_height {
+            format!("Size out of range, resized {target} to {width}x{height} {unit} instead")
+        } else {
+            format!("Resized {target} to {width}x{height} {unit}")
+        });
+
+        // sizing a pane to the size it already has wouldn't show up in any update
+        if self.current_size() != Some((width, height)) {
+            self.resize_in_flight = self
+                .selected_pane
+                .as_ref()
+                .map(|pane| (pane.pane_columns, pane.pane_rows));
+        }
+
+        // the panes map is synced on every session update, so it only holds panes that still exist
+        let targets: Vec<&PaneUi> = if self.select_all {
+            self.panes
+                .values()
+                .filter(|pane| pane.is_floating)
+                .filter(|pane| self.marked.is_empty() || self.marked.contains(&pane.id()))
+                .collect()
         } else {
-            None
+            self.selected_pane.iter().collect()
         };
 
-        resize_floating_pane_by_percent(size, Some(tab_pos.try_into().unwrap()), pane_id);
+        let mut failed = false;
+        let mut resized_ids = Vec::new();
+        for pane in targets {
+            if !pane.is_floating {
+                let screen = (self.screen_cols, self.screen_rows);
+                resize_tiled_pane(pane, self.as_percent(width, height), screen);
+                resized_ids.push(pane.id());
+                continue;
+            }
+            let resized = match self.resize_mode {
+                ResizeMode::Percent => resize_pane_by_percent(pane, width, height),
+                ResizeMode::Cells => resize_pane_by_cells(pane, width, height),
+            };
+            if let Err(message) = resized {
+                self.status_message = Some(message);
+                failed = true;
+                continue;
+            }
+            resized_ids.push(pane.id());
+            if let Err(message) = self.follow_resize(pane, self.in_cells(width, height)) {
+                self.status_message = Some(message);
+                failed = true;
+            }
+        }
 
+        for id in resized_ids {
+            self.mark_resized(id);
+        }
+        if !failed && !self.select_all {
+            self.record_history();
+        }
         self.new_width = 0;
         self.new_height = 0;
+        // a failure stays up so its message can be read
+        if self.auto_hide && !failed {
+            self.deselect_pane();
+            self.hide();
+        } else if self.advance_after_resize && !failed {
+            self.deselect_pane();
+            self.cursor_next();
+        }
+        !failed
     }
 
-    fn handle_key(&mut self, e: Key) {
-        match e {
-            Key::Down => match self.cursor_pane_index {
-                Some(idx) if idx < self.panes.len() => {
-                    self.cursor_pane_index = Some(idx + 1);
-                }
-                Some(idx) if idx == self.panes.len() => {
-                    self.cursor_pane_index = Some(1);
-                }
-                Some(_) => {
-                    unreachable!()
-                }
-                None => self.cursor_pane_index = Some(1),
-            },
-            Key::Up => match self.cursor_pane_index {
-                Some(idx) if idx > 1 => {
-                    self.cursor_pane_index = Some(idx - 1);
-                }
-                Some(idx) if idx == 1 => {
-                    self.cursor_pane_index = Some(self.panes.len());
-                }
-                Some(_) => {
-                    unreachable!()
-                }
-                None => self.cursor_pane_index = Some(1),
-            },
-            Key::Ctrl(c) => {
-                if c == 's' && self.selected_pane.is_some() {
-                    self.send_resize_event();
-                } else if c == 'r' && self.selected_pane.is_some() {
-                    self.new_width = 0;
-                    self.new_height = 0;
-                    self.input_buffer.clear();
-                    self.awaiting_length_input = false;
-                } else if c == 'e' {
-                    close_focus();
-                }
+    fn as_percent(&self, width: u16, height: u16) -> (u16, u16) {
+        match self.resize_mode {
+            ResizeMode::Percent => (width, height),
+            ResizeMode::Cells => {
+                let to_percent =
+                    |cells: u16, screen: usize| (usize::from(cells) * 100 / screen.max(1)) as u16;
+                (
+                    to_percent(width, self.screen_cols),
+                    to_percent(height, self.screen_rows),
+                )
             }
-            Key::Esc => {
-                if self.selected_pane.is_some() {
-                    self.selected_pane = None;
-                    self.new_width = 0;
-                    self.new_height = 0;
-                } else {
-                    hide_self();
-                }
+        }
+    }
+
+    // configured minimum in the unit of the current resize mode, cells need the screen size
+    fn min_percent(&self) -> (u16, u16) {
+        (
+            u16::from(self.config.min_width).max(MIN_PERCENT),
+            u16::from(self.config.min_height).max(MIN_PERCENT),
+        )
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        let (min_width, min_height) = self.min_percent();
+        match self.resize_mode {
+            ResizeMode::Percent => (min_width, min_height),
+            ResizeMode::Cells => {
+                let to_cells =
+                    |percent: u16, screen: usize| (screen * usize::from(percent) / 100) as u16;
+                (
+                    to_cells(min_width, self.screen_cols).max(MIN_CELLS),
+                    to_cells(min_height, self.screen_rows).max(MIN_CELLS),
+                )
             }
-            Key::Delete => {
-                if self.selected_pane.is_some() {
-                    self.selected_pane = None;
-                } else {
-                    hide_self();
-                }
+        }
+    }
+
+    // false if the resize couldn't be sent, the status line then says why
+    fn resize_by_percent(&mut self, width: u16, height: u16) -> bool {
+        let (min_width, min_height) = self.min_percent();
+        let width = width.clamp(min_width, MAX_PERCENT);
+        let height = height.clamp(min_height, MAX_PERCENT);
+        let pane = self.selected_pane.as_ref().unwrap();
+        if self.dry_run {
+            self.status_message = Some(format!(
+                "Dry run: would resize pane {} in tab {} to {width}x{height} percent",
+                pane.pane_id, pane.parent_tab.tab_id
+            ));
+            return false;
+        }
+        let id = pane.id();
+        if !pane.is_floating {
+            resize_tiled_pane(pane, (width, height), (self.screen_cols, self.screen_rows));
+            self.mark_resized(id);
+            return true;
+        }
+        let resized = resize_pane_by_percent(pane, width, height)
+            .and_then(|()| self.follow_resize(pane, self.percent_in_cells(width, height)));
+        if let Err(message) = resized {
+            self.status_message = Some(message);
+            return false;
+        }
+        self.mark_resized(id);
+        true
+    }
+
+    // the selected pane's size relative to the display area, unknown until a tiled pane was seen
+    fn current_size_percent(&self) -> Option<(u16, u16)> {
+        let pane = self.selected_pane.as_ref()?;
+        if self.screen_cols == 0 || self.screen_rows == 0 {
+            return None;
+        }
+        let width = pane.pane_columns * 100 / self.screen_cols;
+        let height = pane.pane_rows * 100 / self.screen_rows;
+        Some((width as u16, height as u16))
+    }
+
+    fn max_size(&self) -> u16 {
+        match self.resize_mode {
+            ResizeMode::Percent => MAX_PERCENT,
+            ResizeMode::Cells => u16::MAX,
+        }
+    }
+
+    // "+10" and "-5" are relative to the pane's current size on that side, plain numbers are absolute
+    fn typed_size(&mut self, is_height: bool) -> Option<u16> {
+        let max = self.max_size();
+        // an empty field keeps the pre-filled default, or without one the side's current size
+        if self.input_buffer.is_empty() {
+            let prefilled = if is_height {
+                self.new_height
+            } else {
+                self.new_width
+            };
+            if prefilled != 0 {
+                return Some(prefilled);
             }
-            Key::Char(c) => match c {
-                '\n' if self.selected_pane.is_none() => {
-                    self.selected_pane = self
-                        .cursor_pane_index
-                        .and_then(|idx| self.panes.get(&idx).cloned());
-                }
-                '\n' if self.selected_pane.is_some() => {
-                    if self.awaiting_length_input {
-                        self.new_height = self.input_buffer.parse::<u8>().unwrap_or(0);
-                        self.input_buffer.clear();
-                        self.awaiting_length_input = false;
-                    } else {
-                        self.new_width = self.input_buffer.parse::<u8>().unwrap_or(0);
-                        self.input_buffer.clear();
-                        self.awaiting_length_input = true;
-                    }
-                }
-                '0'..='9' => {
-                    if self.selected_pane.is_some() {
-                        self.capture_number_input(c);
-                    }
-                }
-                _ => {}
+            let Some((width, height)) = self.current_size() else {
+                self.status_message = Some("Current size unknown, enter a size".to_string());
+                return None;
+            };
+            return Some(if is_height { height } else { width });
+        }
+        let (digits, sign) = match self.input_buffer.strip_prefix('+') {
+            Some(digits) => (digits.to_string(), 1),
+            None => match self.input_buffer.strip_prefix('-') {
+                Some(digits) => (digits.to_string(), -1),
+                None => return self.parse_input(1..=max),
             },
-            _ => {}
+        };
+        let Ok(magnitude) = digits.parse::<i32>() else {
+            self.status_message = Some("Invalid number, enter digits after the sign".to_string());
+            return None;
+        };
+        let Some((width, height)) = self.current_size() else {
+            self.status_message = Some("Current size unknown, enter an absolute size".to_string());
+            return None;
+        };
+        let current = if is_height { height } else { width };
+        Some((i32::from(current) + sign * magnitude).clamp(1, i32::from(max)) as u16)
+    }
+
+    // the typed number if it parses and lies in `range`, otherwise the status line says why not
+    fn parse_input(&mut self, range: RangeInclusive<u16>) -> Option<u16> {
+        match self.input_buffer.parse::<u16>() {
+            Ok(value) if range.contains(&value) => Some(value),
+            Ok(_) => {
+                self.status_message = Some(format!(
+                    "Out of range, enter {} to {}",
+                    range.start(),
+                    range.end()
+                ));
+                None
+            }
+            Err(_) if self.input_buffer.is_empty() => {
+                self.status_message = Some("Enter a number first".to_string());
+                None
+            }
+            Err(_) => {
+                self.status_message = Some("Invalid number".to_string());
+                None
+            }
         }
     }
 
-    fn capture_number_input(&mut self, c: char) {
-        self.input_buffer.push(c);
+    // rounds a typed percentage to the nearest multiple of the configured step, a side left
+    // empty keeps the current size as it is
+    fn snap(&self, value: u16) -> u16 {
+        let step = u16::from(self.config.snap_step);
+        if step == 0
+            || value == 0
+            || self.resize_mode != ResizeMode::Percent
+            || self.input_buffer.is_empty()
+        {
+            return value;
+        }
+        ((value + step / 2) / step * step).clamp(step.min(MAX_PERCENT), MAX_PERCENT)
+    }
+
+    // the selected pane's size in the unit of the current resize mode
+    fn current_size(&self) -> Option<(u16, u16)> {
+        match self.resize_mode {
+            ResizeMode::Percent => self.current_size_percent(),
+            ResizeMode::Cells => self
+                .selected_pane
+                .as_ref()
+                .map(|pane| (pane.pane_columns as u16, pane.pane_rows as u16)),
+        }
+    }
+
+    fn toggle_maximize(&mut self) {
+        if let Some((width, height)) = self.maximized_from.take() {
+            if self.resize_by_percent(width, height) {
+                self.status_message = Some(format!("Restored pane to {width}x{height} percent"));
+            }
+        } else {
+            let restore_size = self
+                .current_size_percent()
+                .filter(|&(width, height)| width < MAXIMIZED_PERCENT || height < MAXIMIZED_PERCENT)
+                .unwrap_or((DEFAULT_RESTORE_PERCENT, DEFAULT_RESTORE_PERCENT));
+            if self.resize_by_percent(MAXIMIZED_PERCENT, MAXIMIZED_PERCENT) {
+                self.maximized_from = Some(restore_size);
+                self.status_message =
+                    Some(format!("Maximized pane to {MAXIMIZED_PERCENT} percent"));
+            }
+        }
+    }
+
+    // the pane's width in cells becomes its height and the other way around,
+    // each converted against the screen side it ends up on
+    fn swap_dimensions(&mut self) {
+        let Some(pane) = &self.selected_pane else {
+            return;
+        };
+        if self.screen_cols == 0 || self.screen_rows == 0 {
+            self.status_message = Some("Screen size unknown, can't swap yet".to_string());
+            return;
+        }
+        let width = cells_to_percent(pane.pane_rows, self.screen_cols);
+        let height = cells_to_percent(pane.pane_columns, self.screen_rows);
+        if self.resize_by_percent(width, height) {
+            self.record_history();
+            self.status_message = Some(format!("Swapped pane to {width}x{height} percent"));
+        }
+    }
+
+    // keeps the pane's width and derives the height from it in cells, both are then
+    // converted to percentages of the screen
+    fn golden_ratio(&mut self) {
+        let Some(pane) = &self.selected_pane else {
+            return;
+        };
+        if self.screen_cols == 0 || self.screen_rows == 0 {
+            self.status_message =
+                Some("Screen size unknown, can't compute the ratio yet".to_string());
+            return;
+        }
+        // percentages of either side, so the ratio holds whatever the cells look like
+        let width = cells_to_percent(pane.pane_columns, self.screen_cols);
+        let height = ((f64::from(width) / GOLDEN_RATIO).round() as u16).max(MIN_PERCENT);
+        if self.resize_by_percent(width, height) {
+            self.record_history();
+            self.status_message = Some(format!(
+                "Golden ratio: resized pane to {width}x{height} percent"
+            ));
+        }
+    }
+
+    fn half_screen(&mut self) {
+        if self.resize_by_percent(HALF_PERCENT, HALF_PERCENT) {
+            self.record_history();
+            self.status_message = Some(format!(
+                "Resized pane to {HALF_PERCENT}x{HALF_PERCENT} percent"
+            ));
+        }
+    }
+
+    // wide enough for CONTENT_COLUMNS columns inside the frame, rounded up so none get cut off
+    fn fit_to_content(&mut self) {
+        let Some((_, height)) = self.current_size_percent() else {
+            self.status_message = Some("Screen size unknown, can't fit the pane yet".to_string());
+            return;
+        };
+        let pane = self.selected_pane.as_ref().unwrap();
+        let frame = pane.pane_columns.saturating_sub(pane.pane_content_columns);
+        let width = (((CONTENT_COLUMNS + frame) * 100).div_ceil(self.screen_cols) as u16)
+            .clamp(MIN_PERCENT, MAX_PERCENT);
+        if self.resize_by_percent(width, height) {
+            self.record_history();
+            self.status_message = Some(format!(
+                "Fit {CONTENT_COLUMNS} columns: resized pane to {width}x{height} percent"
+            ));
+        }
+    }
+
+    // the size from the last update, so still the one from before a resize that was just sent
+    fn record_history(&mut self) {
+        if let Some((width, height)) = self.current_size_percent() {
+            self.push_history(Undo::Size(width, height));
+        }
+    }
+
+    fn push_history(&mut self, entry: Undo) {
+        if self.resize_history.len() == MAX_HISTORY_LEN {
+            self.resize_history.remove(0);
+        }
+        self.resize_history.push(entry);
+    }
+
+    fn undo_resize(&mut self) {
+        match self.resize_history.pop() {
+            Some(Undo::Size(width, height)) if self.selected_pane.is_some() => {
+                if self.resize_by_percent(width, height) {
+                    self.status_message =
+                        Some(format!("Restored pane to {width}x{height} percent"));
+                }
+            }
+            Some(Undo::Size(..)) => {}
+            Some(Undo::Layout(panes)) => self.restore_layout(panes),
+            None => self.status_message = Some("Nothing to undo".to_string()),
+        }
+    }
+
+    // puts the panes of an undone arrangement back where they were, in the size they had
+    fn restore_layout(&mut self, panes: Vec<PaneUi>) {
+        if self.dry_run {
+            self.status_message = Some(format!(
+                "Dry run: would restore the layout of {} pane(s)",
+                panes.len()
+            ));
+            // nothing was put back, so it stays undoable
+            self.resize_history.push(Undo::Layout(panes));
+            return;
+        }
+        if self.screen_cols == 0 || self.screen_rows == 0 {
+            self.status_message = Some("Screen size unknown, can't restore yet".to_string());
+            return;
+        }
+        for pane in &panes {
+            let width = cells_to_percent(pane.pane_columns, self.screen_cols);
+            let height = cells_to_percent(pane.pane_rows, self.screen_rows);
+            let restored = resize_pane_by_percent(pane, width, height)
+                .and_then(|()| move_pane(pane, pane.pane_x, pane.pane_y));
+            if let Err(message) = restored {
+                self.status_message = Some(message);
+                return;
+            }
+        }
+        self.status_message = Some(format!("Restored the layout of {} pane(s)", panes.len()));
+    }
+
+    // tiles the floating panes of every tab into a grid over the whole screen, as close to
+    // square as their count allows; the previous layout goes on the undo stack
+    fn distribute_evenly(&mut self) {
+        if self.browse_only() {
+            return;
+        }
+        let (screen_cols, screen_rows) = (self.screen_cols, self.screen_rows);
+        if screen_cols == 0 || screen_rows == 0 {
+            self.status_message = Some("Screen size unknown, can't arrange yet".to_string());
+            return;
+        }
+        let mut tabs: BTreeMap<usize, Vec<PaneUi>> = BTreeMap::new();
+        for pane in self.panes.values().filter(|pane| pane.is_floating) {
+            tabs.entry(pane.parent_tab.tab_id)
+                .or_default()
+                .push(pane.clone());
+        }
+        for panes in tabs.values_mut() {
+            panes.sort_unstable_by_key(|pane| pane.pane_id);
+        }
+        let count: usize = tabs.values().map(Vec::len).sum();
+        if count == 0 {
+            self.status_message = Some("No floating panes to arrange".to_string());
+            return;
+        }
+        if self.dry_run {
+            self.status_message = Some(format!(
+                "Dry run: would arrange {count} pane(s) into a grid"
+            ));
+            return;
+        }
+        for panes in tabs.values() {
+            let grid_cols = (1..).find(|cols| cols * cols >= panes.len()).unwrap();
+            let grid_rows = panes.len().div_ceil(grid_cols);
+            let (cell_cols, cell_rows) = (screen_cols / grid_cols, screen_rows / grid_rows);
+            // percentages round down so neighbouring cells never overlap
+            let width = cells_to_percent(cell_cols, screen_cols);
+            let height = cells_to_percent(cell_rows, screen_rows);
+            for (position, pane) in panes.iter().enumerate() {
+                let x = position % grid_cols * cell_cols;
+                let y = position / grid_cols * cell_rows;
+                let arranged = resize_pane_by_percent(pane, width, height)
+                    .and_then(|()| move_pane(pane, x, y));
+                if let Err(message) = arranged {
+                    self.status_message = Some(message);
+                }
+            }
+        }
+        self.push_history(Undo::Layout(tabs.into_values().flatten().collect()));
+        if self.status_message.is_none() {
+            self.status_message = Some(format!(
+                "Arranged {count} pane(s) into a grid, <Ctrl+Z> undoes it"
+            ));
+        }
+    }
+
+    // grows or shrinks the selected pane relative to its current size
+    fn nudge_size(&mut self, width_delta: i32, height_delta: i32) {
+        let Some((width, height)) = self.current_size_percent() else {
+            return;
+        };
+        let (min_width, min_height) = self.min_percent();
+        let nudge = |value: u16, delta: i32, min: u16| {
+            (i32::from(value) + delta).clamp(i32::from(min), i32::from(MAX_PERCENT)) as u16
+        };
+        if self.resize_by_percent(
+            nudge(width, width_delta, min_width),
+            nudge(height, height_delta, min_height),
+        ) {
+            self.record_history();
+        }
+    }
+
+    // whatever was typed or committed belongs to one selection and must not leak into the next
+    fn clear_input_state(&mut self) {
+        self.new_width = 0;
+        self.new_height = 0;
+        self.new_x = 0;
+        self.new_y = 0;
+        self.input_buffer.clear();
+        self.awaiting_length_input = false;
+    }
+
+    // back to how the plugin starts out: the configured sizes pre-filled, no aspect lock or
+    // single axis, percentages and the toggles as configured
+    fn restore_defaults(&mut self) {
+        self.clear_input_state();
+        self.new_width = self.config.default_width.into();
+        self.new_height = self.config.default_height.into();
+        self.aspect_lock = None;
+        self.axis = Axis::Both;
+        self.resize_mode = ResizeMode::Percent;
+        self.mode = InteractionMode::Resize;
+        self.dry_run = self.config.dry_run;
+        self.auto_hide = self.config.auto_hide;
+        self.advance_after_resize = self.config.advance_after_resize;
+        self.status_message = Some(format!(
+            "Defaults restored, {}x{} percent",
+            self.new_width, self.new_height
+        ));
+    }
+
+    fn deselect_pane(&mut self) {
+        self.selected_pane = None;
+        self.clear_input_state();
+        self.maximized_from = None;
+        // sizes belong to the pane that was selected, arrangements stay undoable from the list
+        self.resize_history
+            .retain(|entry| matches!(entry, Undo::Layout(_)));
+        self.select_all = false;
+        self.resize_in_flight = None;
+    }
+
+    fn close_pane(&mut self, pane: &PaneUi) {
+        match pane.id() {
+            PaneId::Terminal(id) => close_terminal_pane(id),
+            PaneId::Plugin(id) => close_plugin_pane(id),
+        }
+        if self.selected_pane.is_some() {
+            self.deselect_pane();
+        }
+
+        // drop it right away instead of waiting for the next session update
+        self.panes
+            .retain(|_, remaining_pane| remaining_pane.id() != pane.id());
+        self.clamp_cursor();
+    }
+
+    // zellij only embeds the focused pane, so focus it first
+    fn embed_pane(&mut self) {
+        let Some(pane) = self.selected_pane.clone() else {
+            return;
+        };
+        if !pane.is_floating {
+            self.status_message = Some(format!("Pane {} is already tiled", pane.pane_id));
+            return;
+        }
+        match pane.id() {
+            PaneId::Terminal(id) => focus_terminal_pane(id, true),
+            PaneId::Plugin(id) => focus_plugin_pane(id, true),
+        }
+        toggle_pane_embed_or_eject();
+        self.pending_embed = Some(pane.id());
+        self.deselect_pane();
+        // it is no longer floating, the next session update lists it again if embedding failed
+        self.panes
+            .retain(|_, remaining_pane| remaining_pane.id() != pane.id());
+        self.clamp_cursor();
+    }
+
+    // zellij doesn't report failed embeds, a pane that is still floating is the only sign
+    fn check_embed(&mut self, session: &SessionInfo) {
+        let Some(id) = self.pending_embed.take() else {
+            return;
+        };
+        let still_floating = session
+            .panes
+            .panes
+            .values()
+            .flatten()
+            .any(|pane| pane.is_floating && PaneUi::id_of(pane) == id);
+        if still_floating {
+            self.status_message = Some(
+                "Couldn't embed the pane, the tiled layout may have no room for it".to_string(),
+            );
+        }
+    }
+
+    // jump to the selected pane, or the highlighted one while browsing, and get out of its way
+    fn focus_pane(&mut self) {
+        let pane = self.selected_pane.clone().or_else(|| {
+            self.cursor_pane_index
+                .and_then(|idx| self.panes.get(&idx).cloned())
+        });
+        let Some(pane) = pane else {
+            return;
+        };
+        if let Some(session) = &self.browsed_session {
+            let pane_id = Some((pane.pane_id, pane.is_plugin));
+            switch_session_with_focus(session, Some(pane.parent_tab.tab_id), pane_id);
+            return;
+        }
+        match pane.id() {
+            PaneId::Terminal(id) => focus_terminal_pane(id, true),
+            PaneId::Plugin(id) => focus_plugin_pane(id, true),
+        }
+        self.hide();
+    }
+
+    // presets are bound to F1, F2, ... in the order they are configured
+    fn apply_preset(&mut self, key: u8) {
+        let Some(preset) = usize::from(key)
+            .checked_sub(1)
+            .and_then(|idx| self.config.presets.get(idx))
+        else {
+            return;
+        };
+        let (min_width, min_height) = self.min_percent();
+        let width = preset.width.clamp(min_width, MAX_PERCENT);
+        let height = preset.height.clamp(min_height, MAX_PERCENT);
+        let message = format!("Applied preset {} ({width}x{height} percent)", preset.name);
+        if self.resize_by_percent(width, height) {
+            self.record_history();
+            self.status_message = Some(message);
+        }
+    }
+
+    fn move_selected_pane(&mut self) {
+        self.move_pane_to(usize::from(self.new_x), usize::from(self.new_y));
+
+        self.new_x = 0;
+        self.new_y = 0;
+    }
+
+    fn center_selected_pane(&mut self) {
+        let pane = self.selected_pane.as_ref().unwrap();
+        let x = self.screen_cols.saturating_sub(pane.pane_columns) / 2;
+        let y = self.screen_rows.saturating_sub(pane.pane_rows) / 2;
+        self.move_pane_to(x, y);
+    }
+
+    fn move_pane_to(&mut self, x: usize, y: usize) {
+        if !self.selected_pane.as_ref().unwrap().is_floating {
+            self.status_message = Some("Only floating panes can be moved".to_string());
+            return;
+        }
+        let max_x = self.screen_cols.saturating_sub(MIN_VISIBLE_CELLS);
+        let max_y = self.screen_rows.saturating_sub(MIN_VISIBLE_CELLS);
+        let clamped = x > max_x || y > max_y;
+        let x = x.min(max_x);
+        let y = y.min(max_y);
+        let pane = self.selected_pane.as_ref().unwrap();
+        if self.dry_run {
+            self.status_message = Some(format!(
+                "Dry run: would move pane {} in tab {} to {x},{y}",
+                pane.pane_id, pane.parent_tab.tab_id
+            ));
+            return;
+        }
+        if let Err(message) = move_pane(pane, x, y) {
+            self.status_message = Some(message);
+            return;
+        }
+        self.status_message = Some(if clamped {
+            format!("Position out of screen, moved pane to {x},{y} instead")
+        } else {
+            format!("Moved pane to {x},{y}")
+        });
+    }
+
+    // where a pane resized to the given size has to go for the configured anchor to stay in
+    // place and, with `keep_on_screen`, to fit on screen, if it isn't there already
+    fn resized_position(
+        &self,
+        pane: &PaneUi,
+        (width, height): (usize, usize),
+    ) -> Option<(usize, usize)> {
+        if self.screen_cols == 0 || self.screen_rows == 0 {
+            return None;
+        }
+        // the geometry of the last update is the one from before the resize
+        let (x, y) = self.config.anchor.position(
+            (pane.pane_x, pane.pane_y),
+            (pane.pane_columns, pane.pane_rows),
+            (width, height),
+        );
+        let (x, y) = if self.config.keep_on_screen {
+            (
+                x.min(self.screen_cols.saturating_sub(width)),
+                y.min(self.screen_rows.saturating_sub(height)),
+            )
+        } else {
+            (x, y)
+        };
+        (x != pane.pane_x || y != pane.pane_y).then_some((x, y))
+    }
+
+    // moves a pane that was just resized to the given cells to where `resized_position` wants it
+    fn follow_resize(&self, pane: &PaneUi, cells: (usize, usize)) -> Result<(), String> {
+        match self.resized_position(pane, cells) {
+            Some((x, y)) => move_pane(pane, x, y),
+            None => Ok(()),
+        }
+    }
+
+    // a size in the unit of the current resize mode as columns and rows
+    fn in_cells(&self, width: u16, height: u16) -> (usize, usize) {
+        match self.resize_mode {
+            ResizeMode::Percent => self.percent_in_cells(width, height),
+            ResizeMode::Cells => (usize::from(width), usize::from(height)),
+        }
+    }
+
+    fn percent_in_cells(&self, width: u16, height: u16) -> (usize, usize) {
+        (
+            self.screen_cols * usize::from(width) / 100,
+            self.screen_rows * usize::from(height) / 100,
+        )
+    }
+
+    // the panes shown in the list along with their index
+    fn listed_panes(&self) -> Vec<(usize, PaneUi)> {
+        self.visible_indices()
+            .into_iter()
+            .filter_map(|idx| self.panes.get(&idx).map(|pane| (idx, pane.clone())))
+            .map(|(idx, mut pane)| {
+                pane.is_pinned = self.pinned.contains(&pane.id());
+                pane.is_marked = self.marked.contains(&pane.id());
+                // time spent in another session says nothing about the panes browsed there
+                if self.browsed_session.is_none() {
+                    pane.resized_ago = self.resized_at.get(&pane.id()).map(Instant::elapsed);
+                }
+                (idx, pane)
+            })
+            .collect()
+    }
+
+    // indices of the panes shown in the list, narrowed down by the title filter
+    fn visible_indices(&self) -> Vec<usize> {
+        let query = self.filter.to_lowercase();
+        let mut panes: Vec<(usize, &PaneUi)> = self
+            .panes
+            .iter()
+            .filter(|(_, pane)| pane.name.to_lowercase().contains(&query))
+            .map(|(idx, pane)| (*idx, pane))
+            .collect();
+        // pinned panes first, then grouped by tab, the same order the list is drawn in, the
+        // index breaks ties
+        panes.sort_unstable_by(|(a_idx, a), (b_idx, b)| {
+            let by_mode = match self.sort_mode {
+                SortMode::Index => std::cmp::Ordering::Equal,
+                SortMode::Title => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortMode::Size => {
+                    (b.pane_columns * b.pane_rows).cmp(&(a.pane_columns * a.pane_rows))
+                }
+            };
+            let pinned = |pane: &PaneUi| self.pinned.contains(&pane.id());
+            pinned(b)
+                .cmp(&pinned(a))
+                .then(a.parent_tab.tab_id.cmp(&b.parent_tab.tab_id))
+                .then(by_mode)
+                .then(a_idx.cmp(b_idx))
+        });
+        panes.into_iter().map(|(idx, _)| idx).collect()
+    }
+
+    fn toggle_pin(&mut self) {
+        let Some(pane) = self.cursor_pane_index.and_then(|idx| self.panes.get(&idx)) else {
+            return;
+        };
+        let id = pane.id();
+        self.status_message = Some(if self.pinned.remove(&id) {
+            format!("Unpinned {}", pane.name)
+        } else {
+            self.pinned.insert(id);
+            format!("Pinned {} to the top", pane.name)
+        });
+    }
+
+    fn toggle_mark(&mut self) {
+        let Some(pane) = self.cursor_pane_index.and_then(|idx| self.panes.get(&idx)) else {
+            return;
+        };
+        let id = pane.id();
+        if !self.marked.remove(&id) {
+            self.marked.insert(id);
+        }
+        self.status_message = Some(format!(
+            "{} selected, <Ctrl+A> resizes them",
+            self.marked_count()
+        ));
+    }
+
+    // marks of panes that were closed in the meantime don't count
+    fn marked_count(&self) -> usize {
+        self.panes
+            .values()
+            .filter(|pane| self.marked.contains(&pane.id()))
+            .count()
+    }
+
+    // Ctrl+A only borrows the first pane as the selection, actions on a single pane are off then
+    fn single_pane_selected(&self) -> bool {
+        self.selected_pane.is_some() && !self.select_all
+    }
+
+    fn bulk_target(&self) -> String {
+        if self.marked.is_empty() {
+            "all floating panes".to_string()
+        } else {
+            format!("{} selected panes", self.marked_count())
+        }
+    }
+
+    fn cursor_next(&mut self) {
+        let visible = self.visible_indices();
+        let position = self
+            .cursor_pane_index
+            .and_then(|idx| visible.iter().position(|&visible_idx| visible_idx == idx));
+        self.cursor_pane_index = match position {
+            Some(pos) if pos + 1 < visible.len() => Some(visible[pos + 1]),
+            Some(_) if !self.config.nav_wrap => self.cursor_pane_index,
+            _ => visible.first().copied(),
+        };
+    }
+
+    fn cursor_prev(&mut self) {
+        let visible = self.visible_indices();
+        let position = self
+            .cursor_pane_index
+            .and_then(|idx| visible.iter().position(|&visible_idx| visible_idx == idx));
+        self.cursor_pane_index = match position {
+            Some(pos) if pos > 0 => Some(visible[pos - 1]),
+            Some(_) if !self.config.nav_wrap => self.cursor_pane_index,
+            Some(_) => visible.last().copied(),
+            None => visible.first().copied(),
+        };
+    }
+
+    // a page at a time, stopping at either end before wrapping like the single steps do
+    fn cursor_page(&mut self, forward: bool) {
+        let visible = self.visible_indices();
+        let page = self.page_rows.max(1);
+        let position = self
+            .cursor_pane_index
+            .and_then(|idx| visible.iter().position(|&visible_idx| visible_idx == idx));
+        self.cursor_pane_index = match (position, forward) {
+            (Some(pos), true) if pos + 1 < visible.len() => {
+                Some(visible[(pos + page).min(visible.len() - 1)])
+            }
+            (Some(pos), false) if pos > 0 => Some(visible[pos.saturating_sub(page)]),
+            (Some(_), _) if !self.config.nav_wrap => self.cursor_pane_index,
+            (Some(_), false) => visible.last().copied(),
+            _ => visible.first().copied(),
+        };
+    }
+
+    // when the highlighted pane is gone, move the cursor to the one after it (or the last one)
+    fn clamp_cursor(&mut self) {
+        let visible = self.visible_indices();
+        self.cursor_pane_index = self.cursor_pane_index.and_then(|idx| {
+            visible
+                .iter()
+                .copied()
+                .find(|&visible_idx| visible_idx >= idx)
+                .or_else(|| visible.last().copied())
+        });
+    }
+
+    // keep the cursor on a pane that matches the filter
+    fn sync_cursor_to_filter(&mut self) {
+        let visible = self.visible_indices();
+        if !self
+            .cursor_pane_index
+            .is_some_and(|idx| visible.contains(&idx))
+        {
+            self.cursor_pane_index = visible.first().copied();
+        }
+    }
+
+    fn handle_filter_key(&mut self, e: Key) {
+        match e {
+            Key::Esc => {
+                self.filter.clear();
+                self.filtering = false;
+            }
+            Key::Char('\n') => self.filtering = false,
+            Key::Backspace => {
+                self.filter.pop();
+            }
+            Key::Down => self.cursor_next(),
+            Key::Up => self.cursor_prev(),
+            Key::Char(c) => self.filter.push(c),
+            _ => {}
+        }
+        self.sync_cursor_to_filter();
+    }
+
+    // only the pane list reacts to the mouse
+    fn handle_mouse(&mut self, mouse: Mouse) {
+        if self.permission_denied
+            || self.selected_pane.is_some()
+            || self.pending_close.is_some()
+            || self.session_picker.is_some()
+            || self.show_help
+        {
+            return;
+        }
+        match mouse {
+            Mouse::ScrollUp(_) => self.cursor_prev(),
+            Mouse::ScrollDown(_) => self.cursor_next(),
+            Mouse::LeftClick(line, col) => self.click_row(line, col),
+            Mouse::Hold(line, col) => self.drag_preview(line, col),
+            Mouse::Release(line, col) => self.drag_resize(line, col),
+            _ => {}
+        }
+    }
+
+    // the size the dragged pane gets on release, in cells: every column dragged right or row
+    // dragged down adds a cell, left and up take one away
+    fn dragged_size(&self, line: isize, col: usize) -> Option<(&PaneUi, usize, usize)> {
+        let (idx, origin_line, origin_col) = self.drag_origin?;
+        let cols = col as isize - origin_col as isize;
+        let rows = line - origin_line;
+        if (cols, rows) == (0, 0) {
+            return None;
+        }
+        let pane = self.panes.get(&idx)?;
+        let width = (pane.pane_columns as isize + cols).max(1) as usize;
+        let height = (pane.pane_rows as isize + rows).max(1) as usize;
+        Some((pane, width, height))
+    }
+
+    fn drag_preview(&mut self, line: isize, col: usize) {
+        if let Some((pane, width, height)) = self.dragged_size(line, col) {
+            self.status_message = Some(format!(
+                "Release to resize {} to {width}x{height} cells",
+                pane.name
+            ));
+        }
+    }
+
+    // selects the pane and submits the size as if it was typed, so it gets confirmed and can be
+    // undone the same way
+    fn drag_resize(&mut self, line: isize, col: usize) {
+        let dragged = self
+            .dragged_size(line, col)
+            .map(|(pane, width, height)| (pane.clone(), width, height));
+        self.drag_origin = None;
+        let Some((pane, width, height)) = dragged else {
+            return;
+        };
+        if self.browse_only() {
+            return;
+        }
+        if self.screen_cols == 0 || self.screen_rows == 0 {
+            self.status_message = Some("Screen size unknown, drag again in a moment".to_string());
+            return;
+        }
+        let size = match self.resize_mode {
+            ResizeMode::Percent => (
+                cells_to_percent(width, self.screen_cols),
+                cells_to_percent(height, self.screen_rows),
+            ),
+            ResizeMode::Cells => (width as u16, height as u16),
+        };
+        self.deselect_pane();
+        self.selected_pane = Some(pane);
+        (self.new_width, self.new_height) = size;
+        self.submit_size();
+    }
+
+    fn first_list_row(&self) -> usize {
+        list_first_row(
+            self.browsed_session.is_some(),
+            self.copy_source.is_some(),
+            self.filtering || !self.filter.is_empty(),
+            self.minimap_screen().is_some(),
+        )
+    }
+
+    fn list_area(&self) -> ListArea {
+        ListArea {
+            offset: self.scroll_offset,
+            capacity: self.page_rows,
+            cols: self.list_cols,
+        }
+    }
+
+    // a click moves the cursor to the pane on that row, a second one on the same row selects it
+    fn click_row(&mut self, line: isize, col: usize) {
+        self.drag_origin = None;
+        let first_row = self.first_list_row();
+        let Some(idx) = usize::try_from(line)
+            .ok()
+            .and_then(|line| line.checked_sub(first_row))
+            .and_then(|row| pane_at(&self.listed_panes(), row, col, self.list_area()))
+        else {
+            return;
+        };
+        let now = Instant::now();
+        let double_click = matches!(
+            self.last_click,
+            Some((last_idx, at)) if last_idx == idx && now.duration_since(at) < DOUBLE_CLICK_INTERVAL
+        );
+        self.cursor_pane_index = Some(idx);
+        if double_click {
+            self.last_click = None;
+            self.select_cursor_pane();
+        } else {
+            self.last_click = Some((idx, now));
+            self.drag_origin = Some((idx, line, col));
+        }
+    }
+
+    // what the submit key does in resize mode, a drag in the list ends up here as well
+    fn submit_size(&mut self) {
+        if self.new_width == 0 || self.new_height == 0 {
+            self.status_message = Some("Enter a width and a length first".to_string());
+        } else if self.config.confirm_resize {
+            self.confirming_resize = true;
+        } else {
+            self.send_resize_event();
+        }
+    }
+
+    fn select_cursor_pane(&mut self) {
+        if self.browse_only() {
+            return;
+        }
+        // right after loading nothing is highlighted yet, Enter then means the first pane
+        if self.cursor_pane_index.is_none() {
+            self.cursor_pane_index = self.visible_indices().first().copied();
+        }
+        self.deselect_pane();
+        self.selected_pane = self
+            .cursor_pane_index
+            .and_then(|idx| self.panes.get(&idx).cloned());
+        self.prefill_default_size();
+    }
+
+    // the configured defaults stand in for whatever is left empty at the prompt
+    fn prefill_default_size(&mut self) {
+        if self.selected_pane.is_some() {
+            self.new_width = self.config.default_width.into();
+            self.new_height = self.config.default_height.into();
+        }
+    }
+
+    // Enter selects the pane under the cursor, then commits the typed values one by one
+    fn commit_input(&mut self) -> Commit {
+        if self.copy_source.is_some() && self.selected_pane.is_none() {
+            self.paste_size();
+            return Commit::Nothing;
+        }
+        if self.selected_pane.is_none() {
+            self.select_cursor_pane();
+            return if self.selected_pane.is_some() {
+                Commit::SelectedPane
+            } else {
+                Commit::Nothing
+            };
+        }
+        // "50%" and "%50" read as 50
+        self.input_buffer = self.input_buffer.trim_matches('%').to_string();
+        // an unusable number leaves the pane selected and waiting for the same value again
+        let commit = match (self.mode, self.awaiting_length_input) {
+            (InteractionMode::Move, false) => match self.parse_input(0..=u16::MAX) {
+                Some(x) => {
+                    self.new_x = x;
+                    self.awaiting_length_input = true;
+                    Commit::X
+                }
+                None => Commit::Nothing,
+            },
+            (InteractionMode::Move, true) => match self.parse_input(0..=u16::MAX) {
+                Some(y) => {
+                    self.new_y = y;
+                    self.awaiting_length_input = false;
+                    Commit::Position
+                }
+                None => Commit::Nothing,
+            },
+            (InteractionMode::Resize, true) => match self.typed_size(true) {
+                Some(height) => {
+                    self.new_height = self.snap(height);
+                    self.awaiting_length_input = false;
+                    Commit::Size
+                }
+                None => Commit::Nothing,
+            },
+            (InteractionMode::Resize, false) => self.commit_width(),
+        };
+        self.input_buffer.clear();
+        commit
+    }
+
+    // Alt+Enter gives the typed value to both sides and resizes straight away; a relative
+    // value grows or shrinks each side by the same amount
+    fn resize_both(&mut self) {
+        self.input_buffer = self.input_buffer.trim_matches('%').to_string();
+        if self.input_buffer.is_empty() {
+            self.status_message = Some("Enter a number first".to_string());
+            return;
+        }
+        let (Some(width), Some(height)) = (self.typed_size(false), self.typed_size(true)) else {
+            return;
+        };
+        (self.new_width, self.new_height) = (self.snap(width), self.snap(height));
+        self.input_buffer.clear();
+        self.awaiting_length_input = false;
+        if self.config.confirm_resize {
+            self.confirming_resize = true;
+            return;
+        }
+        let message = format!(
+            "Applying {}x{} {}",
+            self.new_width,
+            self.new_height,
+            self.resize_mode.unit()
+        );
+        if self.send_resize_event() {
+            self.status_message = Some(message);
+        }
+    }
+
+    // the first typed size is the width, unless a single axis or the aspect lock decides
+    // the other side too
+    fn commit_width(&mut self) -> Commit {
+        if let (Axis::Width | Axis::Height, Some((width, height))) =
+            (self.axis, self.current_size())
+        {
+            let Some(typed) = self.typed_size(self.axis == Axis::Height) else {
+                return Commit::Nothing;
+            };
+            (self.new_width, self.new_height) = if self.axis == Axis::Width {
+                (self.snap(typed), height)
+            } else {
+                (width, self.snap(typed))
+            };
+            return Commit::Size;
+        }
+        let Some(width) = self.typed_size(false) else {
+            return Commit::Nothing;
+        };
+        self.new_width = self.snap(width);
+        let Some((ratio_width, ratio_height)) = self.aspect_lock else {
+            self.awaiting_length_input = true;
+            return Commit::Width;
+        };
+        let height = u32::from(self.new_width) * u32::from(ratio_height) / u32::from(ratio_width);
+        let max = u32::from(self.max_size());
+        self.new_height = (height.min(max) as u16).max(MIN_PERCENT);
+        Commit::Size
+    }
+
+    // the highlighted pane only learns the size once it is picked, see `paste_size`
+    fn copy_size(&mut self) {
+        self.copy_source = self.selected_pane.clone();
+        self.deselect_pane();
+        self.status_message = Some("Pick the pane that should get this size".to_string());
+    }
+
+    // the copied size goes over in cells, percentages would round it off
+    fn paste_size(&mut self) {
+        if self.browse_only() {
+            return;
+        }
+        let Some(target) = self.cursor_pane_index.and_then(|idx| self.panes.get(&idx)) else {
+            return;
+        };
+        if !target.is_floating {
+            self.status_message = Some("Only floating panes can take a copied size".to_string());
+            return;
+        }
+        let Some(source) = self.copy_source.take() else {
+            return;
+        };
+        // the source may have been resized since it was picked
+        let source = self
+            .panes
+            .values()
+            .find(|pane| pane.id() == source.id())
+            .cloned()
+            .unwrap_or(source);
+        let (width, height) = (source.pane_columns as u16, source.pane_rows as u16);
+        if self.dry_run {
+            self.status_message = Some(format!(
+                "Dry run: would resize pane {} to {width}x{height} cells",
+                target.pane_id
+            ));
+            return;
+        }
+        let id = target.id();
+        let cells = (usize::from(width), usize::from(height));
+        self.status_message = Some(
+            match resize_pane_by_cells(target, width, height)
+                .and_then(|()| self.follow_resize(target, cells))
+            {
+                Ok(()) => {
+                    let message = format!(
+                        "Resized pane {} to the {width}x{height} cells of {}",
+                        target.pane_id, source.name
+                    );
+                    self.mark_resized(id);
+                    message
+                }
+                Err(message) => message,
+            },
+        );
+    }
+
+    // jumps to the longest typed index that exists, starting over from the new digit otherwise
+    fn jump_to_pane(&mut self, digit: char) {
+        let visible = self.visible_indices();
+        let exists = |digits: &str| {
+            digits
+                .parse::<usize>()
+                .ok()
+                .filter(|idx| visible.contains(idx))
+        };
+        let mut digits = format!("{}{digit}", self.jump_digits);
+        if exists(&digits).is_none() {
+            digits = digit.to_string();
+        }
+        if let Some(idx) = exists(&digits) {
+            self.cursor_pane_index = Some(idx);
+        }
+        self.jump_digits = digits;
+    }
+
+    fn handle_key(&mut self, e: Key) {
+        self.status_message = None;
+        // the same message set again by this key is new and times out afresh
+        self.status_shown = None;
+        if !matches!(e, Key::Alt(CharOrArrow::Char(c)) if c.is_ascii_digit()) {
+            self.jump_digits.clear();
+        }
+        // nothing but leaving, as `on_exit` says, works without the permissions
+        if self.permission_denied {
+            if e == Key::Esc {
+                self.exit();
+            }
+            return;
+        }
+        // closing takes the same key twice in a row, any other key keeps the pane
+        if let Some(pane) = self.pending_close.take() {
+            if e == Key::Ctrl('d') {
+                self.close_pane(&pane);
+            } else {
+                self.status_message = Some(format!("Kept pane {}", pane.pane_id));
+            }
+            return;
+        }
+        if self.confirming_resize {
+            self.confirming_resize = false;
+            if e == Key::Char('y') {
+                self.send_resize_event();
+            }
+            return;
+        }
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+        if self.filtering {
+            self.handle_filter_key(e);
+            return;
+        }
+        if let Some(cursor) = self.session_picker {
+            self.handle_session_picker_key(e, cursor);
+            return;
+        }
+        // with nothing listed, the list only explains how to open a pane
+        let moves_or_picks = matches!(
+            e,
+            Key::Up
+                | Key::Down
+                | Key::Left
+                | Key::Right
+                | Key::PageUp
+                | Key::PageDown
+                | Key::Home
+                | Key::End
+                | Key::BackTab
+                | Key::Alt(_)
+                | Key::Ctrl('a' | 'd' | 'f')
+                | Key::Char('\n' | '\t' | 'j' | 'k' | 'p' | 's')
+        );
+        if self.panes.is_empty() && self.selected_pane.is_none() && moves_or_picks {
+            return;
+        }
+        match e {
+            Key::Alt(CharOrArrow::Char(c))
+                if c.is_ascii_digit() && self.selected_pane.is_none() =>
+            {
+                self.jump_to_pane(c);
+            }
+            Key::Alt(CharOrArrow::Char('\n'))
+                if self.selected_pane.is_some() && self.mode == InteractionMode::Resize =>
+            {
+                self.resize_both();
+            }
+            // only the nudge keys, so other Alt keys still reach the configured bindings
+            Key::Left
+            | Key::Right
+            | Key::Up
+            | Key::Down
+            | Key::Alt(CharOrArrow::Direction(_))
+            | Key::Alt(CharOrArrow::Char('h' | 'j' | 'k' | 'l'))
+                if self.single_pane_selected() && self.mode == InteractionMode::Resize =>
+            {
+                self.nudge_with_key(e);
+            }
+            Key::Down => self.cursor_next(),
+            Key::Up => self.cursor_prev(),
+            // only does something while the list is spread over several columns
+            Key::Left | Key::Right if self.selected_pane.is_none() => {
+                let panes = self.listed_panes();
+                let forward = e == Key::Right;
+                if let Some(idx) =
+                    pane_beside(&panes, self.cursor_pane_index, self.list_area(), forward)
+                {
+                    self.cursor_pane_index = Some(idx);
+                }
+            }
+            // zellij sends Tab as a plain character
+            Key::Char('\t') if self.selected_pane.is_none() => self.cursor_next(),
+            Key::BackTab if self.selected_pane.is_none() => self.cursor_prev(),
+            Key::PageDown if self.selected_pane.is_none() => self.cursor_page(true),
+            Key::PageUp if self.selected_pane.is_none() => self.cursor_page(false),
+            // the visible order is the list order, an empty list leaves the cursor alone
+            Key::Home if self.selected_pane.is_none() => {
+                if let Some(&first) = self.visible_indices().first() {
+                    self.cursor_pane_index = Some(first);
+                }
+            }
+            Key::End if self.selected_pane.is_none() => {
+                if let Some(&last) = self.visible_indices().last() {
+                    self.cursor_pane_index = Some(last);
+                }
+            }
+            // configured bindings take precedence over the built-in ones
+            key if key == self.config.keys.resize && self.selected_pane.is_some() => {
+                match self.mode {
+                    InteractionMode::Move => self.move_selected_pane(),
+                    InteractionMode::Resize => self.submit_size(),
+                }
+            }
+            key if key == self.config.keys.back && self.selected_pane.is_some() => {
+                self.deselect_pane();
+            }
+            // already in the list, swallowed so a back key bound to Esc can't hide the plugin
+            key if key == self.config.keys.back => {}
+            key if key == self.config.keys.reset && self.selected_pane.is_some() => {
+                self.clear_input_state();
+            }
+            key if key == self.config.keys.close => close_focus(),
+            Key::Ctrl(c) => {
+                if c == 'p' && self.selected_pane.is_some() {
+                    self.resize_mode = self.resize_mode.toggle();
+                } else if c == 'c' && self.single_pane_selected() {
+                    self.center_selected_pane();
+                } else if c == 'x' && self.single_pane_selected() {
+                    self.toggle_maximize();
+                } else if c == 'b' && self.single_pane_selected() {
+                    self.embed_pane();
+                } else if c == 't' && self.single_pane_selected() {
+                    self.swap_dimensions();
+                } else if c == 'l' && self.selected_pane.is_some() {
+                    self.aspect_lock = match self.aspect_lock {
+                        Some(_) => None,
+                        None => Some(self.config.aspect_ratio),
+                    };
+                } else if c == 'z' {
+                    self.undo_resize();
+                } else if c == 'w' && self.single_pane_selected() {
+                    self.mode = self.mode.toggle();
+                    self.input_buffer.clear();
+                    self.awaiting_length_input = false;
+                } else if c == 'a' && self.selected_pane.is_none() && !self.browse_only() {
+                    // the first pane stands in as the selection so the resize prompt can be reused
+                    self.selected_pane = self
+                        .panes
+                        .iter()
+                        .filter(|(_, pane)| {
+                            self.marked.is_empty() || self.marked.contains(&pane.id())
+                        })
+                        .min_by_key(|(idx, _)| **idx)
+                        .map(|(_, pane)| pane.clone());
+                    self.select_all = self.selected_pane.is_some();
+                    self.clear_input_state();
+                    self.prefill_default_size();
+                } else if c == 'f' {
+                    self.focus_pane();
+                } else if c == 'd' && !self.browse_only() {
+                    self.pending_close = self.selected_pane.clone().or_else(|| {
+                        self.cursor_pane_index
+                            .and_then(|idx| self.panes.get(&idx).cloned())
+                    });
+                }
+            }
+            Key::Esc => {
+                if self.selected_pane.is_some() {
+                    self.deselect_pane();
+                } else if self.copy_source.take().is_some() {
+                    self.status_message = Some("Size copy cancelled".to_string());
+                } else if !self.filter.is_empty() {
+                    self.filter.clear();
+                    self.sync_cursor_to_filter();
+                } else {
+                    self.exit();
+                }
+            }
+            Key::F(n) if self.single_pane_selected() => self.apply_preset(n),
+            Key::Backspace if self.selected_pane.is_some() => {
+                self.input_buffer.pop();
+            }
+            Key::Delete => {
+                if self.selected_pane.is_some() {
+                    self.deselect_pane();
+                } else {
+                    self.exit();
+                }
+            }
+            Key::Char(c) => match c {
+                '\n' => {
+                    if let Commit::Size = self.commit_input() {
+                        self.status_message = Some(format!(
+                            "Size set to {}x{}, press {} to apply it",
+                            self.new_width, self.new_height, self.config.keys.resize
+                        ));
+                    }
+                }
+                'h' | 'j' | 'k' | 'l' | 'H' | 'J' | 'K' | 'L'
+                    if self.single_pane_selected() && self.mode == InteractionMode::Resize =>
+                {
+                    self.nudge_with_key(e);
+                }
+                '%' if self.selected_pane.is_some()
+                    && self.mode == InteractionMode::Resize
+                    && self.resize_mode == ResizeMode::Percent =>
+                {
+                    self.capture_percent_sign();
+                }
+                '+' | '-'
+                    if self.selected_pane.is_some()
+                        && self.mode == InteractionMode::Resize
+                        && self.input_buffer.is_empty() =>
+                {
+                    self.input_buffer.push(c);
+                }
+                'W' if self.selected_pane.is_some() && self.mode == InteractionMode::Resize => {
+                    self.axis = self.axis.toggle(Axis::Width);
+                }
+                // H is taken by the coarse nudge, V as in vertical
+                'V' if self.selected_pane.is_some() && self.mode == InteractionMode::Resize => {
+                    self.axis = self.axis.toggle(Axis::Height);
+                }
+                'g' if self.single_pane_selected() && self.mode == InteractionMode::Resize => {
+                    self.golden_ratio()
+                }
+                'f' if self.single_pane_selected() && self.mode == InteractionMode::Resize => {
+                    self.fit_to_content()
+                }
+                '=' if self.single_pane_selected() && self.mode == InteractionMode::Resize => {
+                    self.half_screen()
+                }
+                'R' if self.single_pane_selected() && self.mode == InteractionMode::Resize => {
+                    self.remember_size()
+                }
+                'r' if self.selected_pane.is_some() => self.restore_defaults(),
+                'D' => {
+                    self.dry_run = !self.dry_run;
+                    self.status_message = Some(format!(
+                        "Dry run {}",
+                        if self.dry_run { "on" } else { "off" }
+                    ));
+                }
+                'A' => {
+                    self.auto_hide = !self.auto_hide;
+                    self.status_message = Some(format!(
+                        "Auto hide {}",
+                        if self.auto_hide { "on" } else { "off" }
+                    ));
+                }
+                'N' => {
+                    self.advance_after_resize = !self.advance_after_resize;
+                    self.status_message = Some(format!(
+                        "Next pane after a resize {}",
+                        if self.advance_after_resize {
+                            "on"
+                        } else {
+                            "off"
+                        }
+                    ));
+                }
+                '?' => self.show_help = true,
+                'm' => self.show_minimap = !self.show_minimap,
+                'b' if self.selected_pane.is_none() => self.show_size_bars = !self.show_size_bars,
+                'i' => self.show_debug = !self.show_debug,
+                'c' if self.single_pane_selected() => self.copy_size(),
+                '/' if self.selected_pane.is_none() => self.filtering = true,
+                'E' if self.selected_pane.is_none() => self.distribute_evenly(),
+                'p' if self.selected_pane.is_none() => self.toggle_pin(),
+                ' ' if self.selected_pane.is_none() => self.toggle_mark(),
+                'u' if self.selected_pane.is_none() && !self.marked.is_empty() => {
+                    self.marked.clear();
+                    self.status_message = Some("Selection cleared".to_string());
+                }
+                // the cursor follows its pane, which is keyed by index and not by position
+                's' if self.selected_pane.is_none() => {
+                    self.sort_mode = self.sort_mode.next();
+                    self.status_message = Some(format!("Sorted by {}", self.sort_mode.name()));
+                }
+                't' if self.selected_pane.is_none() => {
+                    self.show_tiled = !self.show_tiled;
+                    self.reload_panes();
+                }
+                'T' if self.selected_pane.is_none() => {
+                    self.scope = match self.scope {
+                        Scope::AllTabs => Scope::CurrentTab,
+                        Scope::CurrentTab => Scope::AllTabs,
+                    };
+                    self.reload_panes();
+                }
+                'S' if self.selected_pane.is_none() => {
+                    let current =
+                        self.sessions
+                            .iter()
+                            .position(|session| match &self.browsed_session {
+                                Some(name) => &session.name == name,
+                                None => session.is_current_session,
+                            });
+                    self.session_picker = Some(current.unwrap_or(0));
+                }
+                'j' if self.selected_pane.is_none() => self.cursor_next(),
+                'k' if self.selected_pane.is_none() => self.cursor_prev(),
+                '0'..='9' => {
+                    if self.selected_pane.is_some() {
+                        self.capture_number_input(c);
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // Alt makes the step fine and Shift, which zellij only reports for letters, makes it coarse
+    fn nudge_with_key(&mut self, key: Key) {
+        let config = &self.config;
+        let (direction, step) = match key {
+            Key::Left => (Some(Direction::Left), config.resize_step),
+            Key::Right => (Some(Direction::Right), config.resize_step),
+            Key::Up => (Some(Direction::Up), config.resize_step),
+            Key::Down => (Some(Direction::Down), config.resize_step),
+            Key::Alt(CharOrArrow::Direction(direction)) => (Some(direction), config.fine_step),
+            Key::Alt(CharOrArrow::Char(c)) => (key_direction(c), config.fine_step),
+            Key::Char(c) if c.is_ascii_uppercase() => {
+                (key_direction(c.to_ascii_lowercase()), config.coarse_step)
+            }
+            Key::Char(c) => (key_direction(c), config.resize_step),
+            _ => (None, 0),
+        };
+        let step = i32::from(step);
+        match direction {
+            Some(Direction::Left) => self.nudge_size(-step, 0),
+            Some(Direction::Right) => self.nudge_size(step, 0),
+            Some(Direction::Up) => self.nudge_size(0, -step),
+            Some(Direction::Down) => self.nudge_size(0, step),
+            None => {}
+        }
+    }
+
+    fn capture_number_input(&mut self, c: char) {
+        // three digits are enough for 100 percent or a cell count and keep the parse from overflowing
+        let digits = self
+            .input_buffer
+            .chars()
+            .filter(char::is_ascii_digit)
+            .count();
+        // the percent sign ends the number
+        if digits < MAX_INPUT_LEN && !self.input_buffer.contains('%') {
+            self.input_buffer.push(c);
+        }
+    }
+
+    // only once and right behind the digits, "50%" is taken as 50
+    fn capture_percent_sign(&mut self) {
+        if self.input_buffer.ends_with(|c: char| c.is_ascii_digit()) {
+            self.input_buffer.push('%');
+        }
+    }
+}
+
+// share of the screen side the cells take, screens are assumed to be known
+fn cells_to_percent(cells: usize, screen: usize) -> u16 {
+    ((cells * 100 / screen) as u16).clamp(MIN_PERCENT, MAX_PERCENT)
+}
+
+// tab positions come from session data, a bad one must not take the plugin down
+fn tab_position(pane: &PaneUi) -> Result<u32, String> {
+    u32::try_from(pane.parent_tab.tab_id).map_err(|_| {
+        format!(
+            "Tab {} is out of range, pane {} was left as is",
+            pane.parent_tab.tab_id, pane.pane_id
+        )
+    })
+}
+
+fn move_pane(pane: &PaneUi, x: usize, y: usize) -> Result<(), String> {
+    let tab_pos = tab_position(pane)?;
+    let position = MoveToPosition {
+        x: x as u32,
+        y: y as u32,
+    };
+    move_floating_pane_to(position, Some(tab_pos), Some(pane.id()));
+    Ok(())
+}
+
+fn resize_pane_by_percent(pane: &PaneUi, width: u16, height: u16) -> Result<(), String> {
+    let tab_pos = tab_position(pane)?;
+    let size = ResizeByPercent {
+        width: width as u32,
+        height: height as u32,
+    };
+    resize_floating_pane_by_percent(size, Some(tab_pos), Some(pane.id()));
+    Ok(())
+}
+
+fn resize_pane_by_cells(pane: &PaneUi, width: u16, height: u16) -> Result<(), String> {
+    let tab_pos = tab_position(pane)?;
+    let size = ResizeByCells {
+        width: width as u32,
+        height: height as u32,
+    };
+    resize_floating_pane_by_cells(size, Some(tab_pos), Some(pane.id()));
+    Ok(())
+}
+
+// tiled panes can only be grown or shrunk step by step while focused, so focus the pane and
+// step towards the target; the floating layer may get hidden as focus moves to the tiled pane
+fn resize_tiled_pane(
+    pane: &PaneUi,
+    (width, height): (u16, u16),
+    (screen_cols, screen_rows): (usize, usize),
+) {
+    if screen_cols == 0 || screen_rows == 0 {
+        return;
+    }
+    match pane.id() {
+        PaneId::Terminal(id) => focus_terminal_pane(id, false),
+        PaneId::Plugin(id) => focus_plugin_pane(id, false),
+    }
+    let steps = |cells: usize, screen: usize, target: u16| {
+        let current = (cells * 100 / screen) as i32;
+        (i32::from(target) - current) / i32::from(TILED_RESIZE_STEP_PERCENT)
+    };
+    let width_steps = steps(pane.pane_columns, screen_cols, width);
+    let height_steps = steps(pane.pane_rows, screen_rows, height);
+    for (steps, direction) in [
+        (width_steps, Direction::Right),
+        (height_steps, Direction::Down),
+    ] {
+        let resize = if steps > 0 {
+            Resize::Increase
+        } else {
+            Resize::Decrease
+        };
+        for _ in 0..steps.unsigned_abs() {
+            resize_focused_pane_with_direction(resize, direction);
+        }
+    }
+}
+
+fn key_direction(c: char) -> Option<Direction> {
+    match c {
+        'h' => Some(Direction::Left),
+        'j' => Some(Direction::Down),
+        'k' => Some(Direction::Up),
+        'l' => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // zellij provides this to the wasm plugin, the commands only have to go nowhere on the host
+    #[no_mangle]
+    extern "C" fn host_run_plugin_command() {}
+
+    // a tiled pane filling a 200x50 screen, so percentages can be worked out
+    fn screen_pane() -> PaneInfo {
+        PaneInfo {
+            id: 100,
+            pane_columns: 200,
+            pane_rows: 50,
+            is_selectable: true,
+            ..Default::default()
+        }
+    }
+
+    fn floating_pane(id: u32, title: &str) -> PaneInfo {
+        PaneInfo {
+            id,
+            title: title.to_string(),
+            is_floating: true,
+            is_selectable: true,
+            pane_x: 10,
+            pane_y: 5,
+            pane_columns: 40,
+            pane_rows: 20,
+            ..Default::default()
+        }
+    }
+
+    fn session(floating: &[PaneInfo]) -> Vec<SessionInfo> {
+        let tab = TabInfo {
+            position: 0,
+            name: "Tab #1".to_string(),
+            active: true,
+            ..Default::default()
+        };
+        let mut panes = vec![screen_pane()];
+        panes.extend_from_slice(floating);
+        vec![SessionInfo {
+            name: "main".to_string(),
+            tabs: vec![tab],
+            panes: PaneManifest {
+                panes: HashMap::from([(0, panes)]),
+            },
+            is_current_session: true,
+            ..Default::default()
+        }]
+    }
+
+    // set up the way `load` does it, then fed one session update
+    fn state_with(floating: &[PaneInfo]) -> State {
+        let mut state = State {
+            config: Config::new(&BTreeMap::new()),
+            ..Default::default()
+        };
+        state.update(Event::SessionUpdate(session(floating)));
+        state
+    }
+
+    fn type_keys(state: &mut State, keys: &str) {
+        for c in keys.chars() {
+            state.handle_key(Key::Char(c));
+        }
+    }
+
+    #[test]
+    fn typed_sizes_out_of_range_keep_the_pane_selected() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Char('\n'));
+        for typed in ["0", "255"] {
+            type_keys(&mut state, typed);
+            assert!(state.commit_input() == Commit::Nothing);
+            assert_eq!(
+                state.status_message.as_deref(),
+                Some("Out of range, enter 1 to 100")
+            );
+            assert!(state.selected_pane.is_some());
+            assert_eq!(state.new_width, 0);
+        }
+    }
+
+    #[test]
+    fn typed_100_is_sent_as_is() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Char('\n'));
+        type_keys(&mut state, "100\n100\n");
+        assert_eq!((state.new_width, state.new_height), (100, 100));
+        state.send_resize_event();
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Resized pane 1 to 100x100 percent")
+        );
+    }
+
+    #[test]
+    fn sizes_are_clamped_before_they_are_sent() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Char('\n'));
+        (state.new_width, state.new_height) = (255, 0);
+        state.send_resize_event();
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Below the minimum size, resized pane 1 to 100x10 percent instead")
+        );
+        assert!(state.selected_pane.is_some());
+    }
+
+    #[test]
+    fn input_stops_at_three_digits() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Char('\n'));
+        type_keys(&mut state, "9876543210");
+        assert_eq!(state.input_buffer, "987");
+    }
+
+    #[test]
+    fn update_without_a_current_session_keeps_the_panes() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        let mut other = session(&[]);
+        other[0].is_current_session = false;
+        state.update(Event::SessionUpdate(other));
+        assert!(state.waiting_for_session);
+        assert_eq!(state.panes.len(), 1);
+        state.update(Event::SessionUpdate(session(&[floating_pane(1, "htop")])));
+        assert!(!state.waiting_for_session);
+    }
+
+    fn index_of(state: &State, id: u32) -> Option<usize> {
+        state
+            .panes
+            .iter()
+            .find(|(_, pane)| pane.pane_id == id)
+            .map(|(idx, _)| *idx)
+    }
+
+    #[test]
+    fn panes_keep_their_index_when_one_is_added() {
+        let mut state = state_with(&[floating_pane(1, "htop"), floating_pane(2, "logs")]);
+        let before = (index_of(&state, 1), index_of(&state, 2));
+        state.update(Event::SessionUpdate(session(&[
+            floating_pane(3, "notes"),
+            floating_pane(2, "logs"),
+            floating_pane(1, "htop"),
+        ])));
+        assert_eq!((index_of(&state, 1), index_of(&state, 2)), before);
+        assert_eq!(index_of(&state, 3), Some(3));
+    }
+
+    #[test]
+    fn navigating_after_a_close_stays_on_listed_panes() {
+        let panes = [
+            floating_pane(1, "htop"),
+            floating_pane(2, "logs"),
+            floating_pane(3, "notes"),
+        ];
+        let mut state = state_with(&panes);
+        state.handle_key(Key::End);
+        assert_eq!(state.cursor_pane_index, index_of(&state, 3));
+        state.update(Event::SessionUpdate(session(&panes[..2])));
+        assert_eq!(state.cursor_pane_index, index_of(&state, 2));
+        state.handle_key(Key::Down);
+        assert_eq!(state.cursor_pane_index, index_of(&state, 1));
+        state.update(Event::SessionUpdate(session(&[])));
+        assert_eq!(state.cursor_pane_index, None);
+        state.handle_key(Key::Down);
+        assert_eq!(state.cursor_pane_index, None);
+    }
+
+    #[test]
+    fn reselecting_starts_from_a_clean_input() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Char('\n'));
+        type_keys(&mut state, "40\n2");
+        assert!(state.awaiting_length_input);
+        state.handle_key(Key::Esc);
+        assert!(state.selected_pane.is_none());
+        state.handle_key(Key::Char('\n'));
+        assert!(state.selected_pane.is_some());
+        assert_eq!(state.input_buffer, "");
+        assert!(!state.awaiting_length_input);
+        assert_eq!((state.new_width, state.new_height), (0, 0));
+    }
+
+    #[test]
+    fn width_then_height_then_resize() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        assert!(state.commit_input() == Commit::SelectedPane);
+        type_keys(&mut state, "60");
+        assert!(state.commit_input() == Commit::Width);
+        assert!(state.awaiting_length_input);
+        type_keys(&mut state, "40");
+        assert!(state.commit_input() == Commit::Size);
+        assert_eq!((state.new_width, state.new_height), (60, 40));
+        state.handle_key(state.config.keys.resize);
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Resized pane 1 to 60x40 percent")
+        );
+        assert_eq!((state.new_width, state.new_height), (0, 0));
+    }
+
+    #[test]
+    fn x_then_y_in_move_mode() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        assert!(state.commit_input() == Commit::SelectedPane);
+        state.handle_key(Key::Ctrl('w'));
+        type_keys(&mut state, "12");
+        assert!(state.commit_input() == Commit::X);
+        type_keys(&mut state, "7");
+        assert!(state.commit_input() == Commit::Position);
+        assert_eq!((state.new_x, state.new_y), (12, 7));
+    }
+
+    #[test]
+    fn nothing_is_committed_without_a_pane_to_select() {
+        let mut state = state_with(&[]);
+        assert!(state.commit_input() == Commit::Nothing);
+        assert!(state.selected_pane.is_none());
+    }
+
+    #[test]
+    fn empty_and_non_numeric_input_is_reported() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Char('\n'));
+        state.handle_key(Key::Ctrl('w'));
+        assert!(state.commit_input() == Commit::Nothing);
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Enter a number first")
+        );
+        state.input_buffer = "4x".to_string();
+        assert!(state.commit_input() == Commit::Nothing);
+        assert_eq!(state.status_message.as_deref(), Some("Invalid number"));
+        assert!(state.selected_pane.is_some());
+        assert_eq!((state.new_x, state.new_y), (0, 0));
+    }
+
+    #[test]
+    fn an_empty_size_keeps_the_current_side() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Char('\n'));
+        assert!(state.commit_input() == Commit::Width);
+        assert!(state.commit_input() == Commit::Size);
+        // 40x20 cells of a 200x50 screen
+        assert_eq!((state.new_width, state.new_height), (20, 40));
+    }
+
+    #[test]
+    fn rapid_navigation_between_rebuilds_stays_on_listed_panes() {
+        let panes: Vec<PaneInfo> = (1..=6)
+            .map(|id| floating_pane(id, &format!("pane {id}")))
+            .collect();
+        let mut state = state_with(&panes);
+        for round in 0..200 {
+            let key = if round % 3 == 0 { Key::Up } else { Key::Down };
+            state.handle_key(key);
+            // panes close and come back while the keys keep coming
+            if round % 5 == 0 {
+                let open = (round / 5) % panes.len();
+                state.update(Event::SessionUpdate(session(&panes[..open])));
+            }
+            // unset is fine, the next key starts from the first pane again
+            if let Some(idx) = state.cursor_pane_index {
+                assert!(state.visible_indices().contains(&idx));
+            }
+        }
+        state.update(Event::SessionUpdate(session(&panes)));
+        state.handle_key(Key::Down);
+        assert!(state.cursor_pane_index.is_some());
+    }
+
+    #[test]
+    fn a_second_ctrl_d_closes_the_pane() {
+        let mut state = state_with(&[floating_pane(1, "htop"), floating_pane(2, "logs")]);
+        state.handle_key(Key::Home);
+        state.handle_key(Key::Ctrl('d'));
+        assert_eq!(
+            state.pending_close.as_ref().map(|pane| pane.pane_id),
+            Some(1)
+        );
+        state.handle_key(Key::Ctrl('d'));
+        assert!(state.pending_close.is_none());
+        assert_eq!(index_of(&state, 1), None);
+        assert!(index_of(&state, 2).is_some());
+    }
+
+    #[test]
+    fn any_other_key_keeps_the_pane() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Home);
+        state.handle_key(Key::Ctrl('d'));
+        state.handle_key(Key::Down);
+        assert!(state.pending_close.is_none());
+        assert!(index_of(&state, 1).is_some());
+        assert_eq!(state.status_message.as_deref(), Some("Kept pane 1"));
+    }
+
+    #[test]
+    fn nudges_stop_at_the_minimum_size() {
+        // 10 percent of the screen wide, as narrow as `min_width` allows
+        let narrow = PaneInfo {
+            pane_columns: 20,
+            ..floating_pane(1, "htop")
+        };
+        let mut state = state_with(&[narrow]);
+        state.dry_run = true;
+        state.handle_key(Key::Char('\n'));
+        state.handle_key(Key::Left);
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Dry run: would resize pane 1 in tab 0 to 10x40 percent")
+        );
+    }
+
+    #[test]
+    fn tiled_panes_leave_out_the_bars() {
+        let status_bar = PaneInfo {
+            id: 101,
+            is_plugin: true,
+            is_selectable: false,
+            pane_y: 49,
+            pane_columns: 200,
+            pane_rows: 1,
+            ..Default::default()
+        };
+        let mut state = state_with(&[status_bar]);
+        state.handle_key(Key::Char('t'));
+        let listed: Vec<u32> = state.panes.values().map(|pane| pane.pane_id).collect();
+        assert_eq!(listed, [100]);
+    }
+
+    #[test]
+    fn golden_ratio_divides_the_width_percentage() {
+        // 80 of 200 columns are 40 percent, 40 / 1.618 rounds to 25
+        let wide = PaneInfo {
+            pane_columns: 80,
+            ..floating_pane(1, "htop")
+        };
+        let mut state = state_with(&[wide]);
+        state.handle_key(Key::Char('\n'));
+        state.handle_key(Key::Char('g'));
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Golden ratio: resized pane to 40x25 percent")
+        );
+    }
+
+    #[test]
+    fn select_all_starts_from_the_default_size() {
+        let mut state = state_with(&[floating_pane(1, "htop"), floating_pane(2, "logs")]);
+        state.config.default_width = 30;
+        state.config.default_height = 40;
+        state.handle_key(Key::Ctrl('a'));
+        assert!(state.select_all);
+        assert_eq!((state.new_width, state.new_height), (30, 40));
+    }
+
+    #[test]
+    fn a_restored_selection_starts_from_the_default_size() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.config.default_width = 30;
+        state.restore_pane = Some((PaneId::Terminal(1), true));
+        state.update(Event::SessionUpdate(session(&[floating_pane(1, "htop")])));
+        assert_eq!(
+            state.selected_pane.as_ref().map(|pane| pane.pane_id),
+            Some(1)
+        );
+        assert_eq!(state.new_width, 30);
+    }
+
+    #[test]
+    fn aspect_locked_cell_heights_go_past_100() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.config.aspect_ratio = (1, 1);
+        state.handle_key(Key::Char('\n'));
+        state.handle_key(Key::Ctrl('p'));
+        state.handle_key(Key::Ctrl('l'));
+        type_keys(&mut state, "150");
+        assert!(state.commit_width() == Commit::Size);
+        assert_eq!((state.new_width, state.new_height), (150, 150));
+    }
+
+    #[test]
+    fn esc_without_permissions_leaves_as_configured() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Home);
+        state.permission_denied = true;
+        state.handle_key(Key::Esc);
+        assert_eq!(state.restore_pane, Some((PaneId::Terminal(1), false)));
+    }
+
+    #[test]
+    fn only_sent_resizes_can_be_undone() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Char('\n'));
+        state.dry_run = true;
+        state.half_screen();
+        state.nudge_size(1, 1);
+        assert!(state.resize_history.is_empty());
+        state.dry_run = false;
+        state.half_screen();
+        assert_eq!(state.resize_history.len(), 1);
+    }
+
+    #[test]
+    fn single_pane_actions_are_off_while_everything_is_selected() {
+        let mut state = state_with(&[floating_pane(1, "htop"), floating_pane(2, "logs")]);
+        state.dry_run = true;
+        state.handle_key(Key::Ctrl('a'));
+        for key in [Key::Char('='), Key::Char('g'), Key::Ctrl('x'), Key::Left] {
+            state.handle_key(key);
+            assert!(state.status_message.is_none());
+        }
+        assert!(state.select_all);
+    }
+
+    #[test]
+    fn one_shot_actions_on_a_tiled_pane() {
+        let mut state = state_with(&[]);
+        state.handle_key(Key::Char('t'));
+        state.handle_key(Key::Char('\n'));
+        assert_eq!(
+            state.selected_pane.as_ref().map(|pane| pane.pane_id),
+            Some(100)
+        );
+        state.handle_key(Key::Char('='));
+        assert!(state.resized_at.contains_key(&PaneId::Terminal(100)));
+        state.handle_key(Key::Ctrl('c'));
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Only floating panes can be moved")
+        );
+    }
+
+    #[test]
+    fn dry_runs_neither_move_nor_restore_panes() {
+        let mut state = state_with(&[floating_pane(1, "htop"), floating_pane(2, "logs")]);
+        state.handle_key(Key::Char('E'));
+        state.dry_run = true;
+        state.handle_key(Key::Ctrl('z'));
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Dry run: would restore the layout of 2 pane(s)")
+        );
+        assert_eq!(state.resize_history.len(), 1);
+        state.handle_key(Key::Char('\n'));
+        state.handle_key(Key::Ctrl('c'));
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Dry run: would move pane 1 in tab 0 to 80,15")
+        );
+    }
+
+    #[test]
+    fn one_percent_sign_ends_the_number() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.handle_key(Key::Char('\n'));
+        type_keys(&mut state, "%5%%0");
+        assert_eq!(state.input_buffer, "5%");
+    }
+
+    #[test]
+    fn an_empty_size_takes_the_default() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.config.default_width = 60;
+        state.config.default_height = 60;
+        state.handle_key(Key::Char('\n'));
+        assert!(state.commit_input() == Commit::Width);
+        assert!(state.commit_input() == Commit::Size);
+        assert_eq!((state.new_width, state.new_height), (60, 60));
+    }
+
+    #[test]
+    fn a_drag_is_submitted_like_a_typed_size() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.config.confirm_resize = true;
+        let idx = index_of(&state, 1).unwrap();
+        state.drag_origin = Some((idx, 5, 10));
+        state.handle_mouse(Mouse::Release(6, 12));
+        // 42x21 cells of a 200x50 screen
+        assert_eq!((state.new_width, state.new_height), (21, 42));
+        assert!(state.confirming_resize);
+        assert_eq!(
+            state.selected_pane.as_ref().map(|pane| pane.pane_id),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn a_double_click_leaves_nothing_to_drag() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        // what rendering into an 80x20 plugin pane sets
+        (state.page_rows, state.list_cols) = (10, 80);
+        let line = (0..20)
+            .find(|&line| {
+                state.click_row(line, 0);
+                state.cursor_pane_index.is_some()
+            })
+            .unwrap();
+        state.click_row(line, 0);
+        assert!(state.selected_pane.is_some());
+        assert!(state.drag_origin.is_none());
+    }
+
+    #[test]
+    fn a_status_timer_ending_early_still_clears_the_message() {
+        let mut state = state_with(&[floating_pane(1, "htop")]);
+        state.status_message = Some("Resized pane 1".to_string());
+        state.status_expires = Some(Instant::now() + TIMER_SLACK / 2);
+        state.update(Event::Timer(STATUS_TIMEOUT_SECS));
+        assert!(state.status_message.is_none());
+        state.status_message = Some("Resized pane 1".to_string());
+        state.status_expires = Some(Instant::now() + Duration::from_secs(2));
+        state.update(Event::Timer(STATUS_TIMEOUT_SECS));
+        assert!(state.status_message.is_some());
     }
 }
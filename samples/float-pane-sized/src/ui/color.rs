@@ -1,12 +1,76 @@
 use zellij_tile::prelude::{Palette, PaletteColor};
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub struct Colors {
     pub palette: Palette,
+    pub overrides: ColorOverrides,
+}
+
+// zellij's default palette is all black, which is what gets drawn until the first mode update
+// brings the theme, so the standard terminal colors stand in for it
+impl Default for Colors {
+    fn default() -> Self {
+        let eight_bit = PaletteColor::EightBit;
+        Colors::new(Palette {
+            fg: eight_bit(7),
+            // a dark gray, black would hide the highlighted row on most terminals
+            bg: eight_bit(238),
+            black: eight_bit(0),
+            red: eight_bit(1),
+            green: eight_bit(2),
+            yellow: eight_bit(3),
+            blue: eight_bit(4),
+            magenta: eight_bit(5),
+            cyan: eight_bit(6),
+            white: eight_bit(7),
+            orange: eight_bit(208),
+            gray: eight_bit(8),
+            purple: eight_bit(93),
+            gold: eight_bit(220),
+            silver: eight_bit(250),
+            pink: eight_bit(213),
+            brown: eight_bit(130),
+            ..Palette::default()
+        })
+    }
+}
+
+// user-configured colors that win over the zellij palette
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ColorOverrides {
+    pub selected: Option<PaletteColor>,
+    pub cursor: Option<PaletteColor>,
+    pub border: Option<PaletteColor>,
+    pub text: Option<PaletteColor>,
 }
+
 impl Colors {
     pub fn new(palette: Palette) -> Self {
-        Colors { palette }
+        Colors {
+            palette,
+            overrides: ColorOverrides::default(),
+        }
+    }
+
+    pub fn with_overrides(self, overrides: ColorOverrides) -> Self {
+        Colors { overrides, ..self }
+    }
+
+    // background of the highlighted row
+    pub fn selected_bg(&self) -> PaletteColor {
+        self.overrides.selected.unwrap_or(self.palette.bg)
+    }
+
+    pub fn cursor(&self, text: &str) -> String {
+        self.color(&self.overrides.cursor.unwrap_or(self.palette.green), text)
+    }
+
+    pub fn border(&self, text: &str) -> String {
+        self.color(&self.overrides.border.unwrap_or(self.palette.cyan), text)
+    }
+
+    pub fn text(&self, text: &str) -> String {
+        self.color(&self.overrides.text.unwrap_or(self.palette.magenta), text)
     }
     pub fn bold(&self, text: &str) -> String {
         format!("\u{1b}[1m{}\u{1b}[22m", text)
@@ -38,10 +102,6 @@ impl Colors {
         self.color(&self.palette.cyan, text)
     }
 
-    pub fn magenta(&self, text: &str) -> String {
-        self.color(&self.palette.magenta, text)
-    }
-
     pub fn blue(&self, text: &str) -> String {
         self.color(&self.palette.blue, text)
     }
@@ -50,3 +110,30 @@ impl Colors {
         self.color(&self.palette.pink, text)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_colors_are_not_all_black() {
+        let colors = Colors::default();
+        let palette = colors.palette;
+        let black = PaletteColor::EightBit(0);
+        for color in [
+            palette.fg,
+            palette.bg,
+            palette.green,
+            palette.cyan,
+            palette.magenta,
+            palette.orange,
+            palette.blue,
+        ] {
+            assert_ne!(color, black);
+        }
+        // the highlighted row has to stand out from the text drawn on it
+        assert_ne!(colors.selected_bg(), palette.fg);
+        assert_ne!(colors.selected_bg(), palette.green);
+        assert_eq!(colors.cursor(">"), "\u{1b}[38;5;2;1m>\u{1b}[39;22m");
+    }
+}
@@ -1,8 +1,14 @@
-use zellij_tile::prelude::{PaletteColor, PaneInfo, TabInfo};
+use std::time::Duration;
+
+use zellij_tile::prelude::{PaletteColor, PaneId, PaneInfo, TabInfo};
 
 use super::color::Colors;
 use super::tabs::TabUi;
-const MAX_PATH_LEN: usize = 20;
+// titles are never cut shorter than this, however narrow the plugin is
+const MIN_TITLE_LEN: usize = 8;
+const MAX_COMMAND_LEN: usize = 30;
+// one block per tenth of the screen width
+pub const SIZE_BAR_LEN: usize = 10;
 
 #[derive(Default, Debug, Clone)]
 pub struct PaneUi {
@@ -10,6 +16,7 @@ pub struct PaneUi {
     pub pane_id: u32,
     pub is_plugin: bool,
     pub is_focused: bool,
+    pub is_floating: bool,
     pub pane_x: usize,
     pub pane_content_x: usize,
     pub pane_y: usize,
@@ -18,6 +25,14 @@ pub struct PaneUi {
     pub pane_content_rows: usize,
     pub pane_columns: usize,
     pub pane_content_columns: usize,
+    // only known for panes opened to run a command, not for plain shells
+    pub command: Option<String>,
+    // set by the list, zellij knows nothing about pins
+    pub is_pinned: bool,
+    // part of the panes a bulk resize hits, set by the list as well
+    pub is_marked: bool,
+    // time since the plugin last resized it, also set by the list
+    pub resized_ago: Option<Duration>,
     pub parent_tab: TabUi,
 }
 
@@ -28,6 +43,7 @@ impl PaneUi {
             pane_id: pane.id,
             is_plugin: pane.is_plugin,
             is_focused: pane.is_focused,
+            is_floating: pane.is_floating,
             pane_x: pane.pane_x,
             pane_content_x: pane.pane_content_x,
             pane_y: pane.pane_y,
@@ -36,9 +52,29 @@ impl PaneUi {
             pane_content_rows: pane.pane_content_rows,
             pane_columns: pane.pane_columns,
             pane_content_columns: pane.pane_content_columns,
+            command: pane.terminal_command.clone(),
+            is_pinned: false,
+            is_marked: false,
+            resized_ago: None,
             parent_tab: TabUi::new(tab),
         }
     }
+
+    pub fn id(&self) -> PaneId {
+        if self.is_plugin {
+            PaneId::Plugin(self.pane_id)
+        } else {
+            PaneId::Terminal(self.pane_id)
+        }
+    }
+
+    pub fn id_of(pane: &PaneInfo) -> PaneId {
+        if pane.is_plugin {
+            PaneId::Plugin(pane.id)
+        } else {
+            PaneId::Terminal(pane.id)
+        }
+    }
 }
 
 pub struct DrawPaneLine<'p> {
@@ -47,6 +83,10 @@ pub struct DrawPaneLine<'p> {
     pub is_current: Option<usize>,
     pub colors: Colors,
     pub line: String,
+    // the highlight background spans the whole screen row instead of only the line
+    pub fill_row: bool,
+    // screen columns to draw the width of the pane against, when the bars are shown
+    pub size_bar: Option<usize>,
 }
 
 impl<'p> DrawPaneLine<'p> {
@@ -62,10 +102,12 @@ impl<'p> DrawPaneLine<'p> {
             is_current,
             colors,
             line: "".into(),
+            fill_row: true,
+            size_bar: None,
         }
     }
 
-    pub fn draw(&mut self, index: usize) {
+    pub fn draw(&mut self, index: usize, title_width: usize) {
         let focused_text = if self.pane.is_focused {
             self.colors.blue("Yes")
         } else {
@@ -73,27 +115,89 @@ impl<'p> DrawPaneLine<'p> {
         };
         let selected_indicator = if let Some(selected) = self.is_current {
             if selected == index {
-                self.colors.green(">")
+                self.colors.cursor(">")
             } else {
-                self.colors.green(" ")
+                self.colors.cursor(" ")
             }
         } else {
-            self.colors.green(" ")
+            self.colors.cursor(" ")
+        };
+        let index_color = self.colors.text(&(index.to_string()));
+        let pane_id = self.colors.text(&(self.pane.pane_id.to_string()));
+        let focus = self.colors.text("Focus");
+        // pad before coloring so the escape codes don't throw off the column
+        let size = format!(
+            "{:>9}",
+            format!("{}x{}", self.pane.pane_columns, self.pane.pane_rows)
+        );
+        let size = if self.pane.is_floating {
+            self.colors.cyan(&size)
+        } else {
+            format!(
+                "{} {}",
+                self.colors.cyan(&size),
+                self.colors.orange("tiled")
+            )
+        };
+        // a fixed-width column so terminal and plugin names stay aligned
+        let kind = if self.pane.is_plugin {
+            self.colors.blue("P")
+        } else {
+            " ".to_string()
+        };
+        let command = match &self.pane.command {
+            Some(command) => format!(" $ {}", self.colors.bold(&end_truncate(command))),
+            None => String::new(),
+        };
+        let bar = match self.size_bar {
+            Some(screen_cols) => format!(
+                " {}",
+                size_bar(self.pane.pane_columns, screen_cols, self.colors)
+            ),
+            None => String::new(),
+        };
+        let resized = match self.pane.resized_ago {
+            Some(ago) => format!(" {}", self.colors.text(&resized_label(ago))),
+            None => String::new(),
+        };
+        let pin = if self.pane.is_pinned {
+            self.colors.orange("*")
+        } else {
+            " ".to_string()
+        };
+        let mark = if self.pane.is_marked {
+            self.colors.green("✓")
+        } else {
+            " ".to_string()
         };
-        let index_color = self.colors.magenta(&(index.to_string()));
-        let pane_id = self.colors.magenta(&(self.pane.pane_id.to_string()));
-        let focus = self.colors.magenta("Focus");
         let line = format!(
-            "{}{}: [{}] {:<20} (ID: {}, {}: {})",
+            "{}{}: {}{}{} {:<title_width$} {}{} (ID: {}, {}: {}){}{}",
             selected_indicator,
             index_color,
-            self.pane.parent_tab.name,
-            middle_truncate(&self.pane.name),
+            mark,
+            pin,
+            kind,
+            ellipsize(&self.pane.name, title_width),
+            size,
+            bar,
             pane_id,
             focus,
-            focused_text
+            focused_text,
+            resized,
+            command
         );
 
+        // edges in the border color frame the highlighted row on top of its background,
+        // the other rows get a blank in their place to stay aligned
+        let line = if self.is_current == Some(index) {
+            format!(
+                "{}{line}{}",
+                self.colors.border("▌"),
+                self.colors.border("▐")
+            )
+        } else {
+            format!(" {line}")
+        };
         self.line.push_str(&line);
 
         if let Some(selected) = self.is_current {
@@ -105,20 +209,18 @@ impl<'p> DrawPaneLine<'p> {
         }
     }
 
-    // set the background color to green
+    // set the background to the selected color
     fn make_highlight(&mut self) {
         if self.is_current.is_some() {
-            match self.colors.palette.bg {
-                PaletteColor::EightBit(byte) => {
-                    self.line = format!("\x1b[48;5;{byte}m\x1b[K\r\x1b[48;5;{byte}m{}", self.line);
-                }
-                PaletteColor::Rgb((r, g, b)) => {
-                    self.line = format!(
-                        "\x1b[48;2;{};{};{}m\x1b[K\r\x1b[48;2;{};{};{}m{}",
-                        r, g, b, r, g, b, self.line
-                    );
-                }
-            }
+            let background = match self.colors.selected_bg() {
+                PaletteColor::EightBit(byte) => format!("\x1b[48;5;{byte}m"),
+                PaletteColor::Rgb((r, g, b)) => format!("\x1b[48;2;{r};{g};{b}m"),
+            };
+            self.line = if self.fill_row {
+                format!("{background}\x1b[K\r{background}{}", self.line)
+            } else {
+                format!("{background}{}\x1b[49m", self.line)
+            };
         }
     }
 
@@ -127,10 +229,70 @@ impl<'p> DrawPaneLine<'p> {
         self.line = format!("\x1b[49m{}", self.line);
     }
 }
-fn middle_truncate(s: &str) -> String {
-    if s.len() > MAX_PATH_LEN {
-        let part_len = (MAX_PATH_LEN - 1) / 2;
-        format!("{}~{}", &s[0..part_len], &s[s.len() - part_len..])
+
+// cut on characters rather than bytes so titles with wide or accented letters don't panic
+fn ellipsize(s: &str, width: usize) -> String {
+    if s.chars().count() > width {
+        let kept: String = s.chars().take(width.saturating_sub(1)).collect();
+        format!("{kept}…")
+    } else {
+        s.to_string()
+    }
+}
+
+// the widest title column that fits every row in `max_cols`, no wider than the longest title
+pub fn title_width(panes: &[(usize, PaneUi)], max_cols: usize) -> usize {
+    let room = panes
+        .iter()
+        .map(|(index, pane)| room_besides_title(pane, *index))
+        .max()
+        .unwrap_or(0);
+    let longest = panes
+        .iter()
+        .map(|(_, pane)| pane.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    longest
+        .min(max_cols.saturating_sub(room))
+        .max(MIN_TITLE_LEN)
+}
+
+// columns `DrawPaneLine::draw` prints besides the title and the command, frame edges included
+fn room_besides_title(pane: &PaneUi, index: usize) -> usize {
+    let tiled = if pane.is_floating { 0 } else { " tiled".len() };
+    let focused = if pane.is_focused { "Yes" } else { "No" };
+    let details = format!(" (ID: {}, Focus: {focused})", pane.pane_id);
+    // edges, cursor, "<index>: ", mark, pin and kind, the blanks around the title and the size
+    2 + 1 + index.to_string().len() + 2 + 3 + 2 + 9 + tiled + details.len()
+}
+
+// the share of the screen width the pane takes, always SIZE_BAR_LEN wide so rows stay aligned
+fn size_bar(pane_columns: usize, screen_cols: usize, colors: Colors) -> String {
+    let filled = (pane_columns * SIZE_BAR_LEN)
+        .div_ceil(screen_cols.max(1))
+        .min(SIZE_BAR_LEN);
+    format!(
+        "{}{}",
+        colors.green(&"█".repeat(filled)),
+        "░".repeat(SIZE_BAR_LEN - filled)
+    )
+}
+
+// minutes are as precise as it gets, the labels are only redrawn every so often
+fn resized_label(ago: Duration) -> String {
+    let minutes = ago.as_secs() / 60;
+    match minutes {
+        0 => "resized just now".to_string(),
+        1..=59 => format!("resized {minutes}m ago"),
+        _ => format!("resized {}h ago", minutes / 60),
+    }
+}
+
+// the program comes first in a command line, so the end is what gets cut off
+fn end_truncate(s: &str) -> String {
+    if s.chars().count() > MAX_COMMAND_LEN {
+        let kept: String = s.chars().take(MAX_COMMAND_LEN - 1).collect();
+        format!("{kept}~")
     } else {
         s.to_string()
     }
@@ -1,37 +1,328 @@
 use std::io::{self, Write};
 
+use zellij_tile::prelude::Key;
+
+use crate::config::{KeyBindings, OnExit, Preset};
+use crate::{Axis, ResizeMode};
+
 use super::color::Colors;
-use super::panes::{DrawPaneLine, PaneUi};
+use super::panes::{title_width, DrawPaneLine, PaneUi, SIZE_BAR_LEN};
+
+const MINIMAP_COLS: usize = 40;
+const MINIMAP_ROWS: usize = 10;
+// title and separator printed by the headers
+const HEADER_ROWS: usize = 2;
+// status line, separator and key hints at the bottom
+const FOOTER_ROWS: usize = 3;
+// plugin panes this short only get the compact view
+const COMPACT_MAX_ROWS: usize = 3;
+// narrowest a column of the pane list gets before the list stays in a single one
+const LIST_COLUMN_MIN_WIDTH: usize = 70;
+
+// the committed sizes along with whatever is being typed for the next one
+pub struct SizeInput<'i> {
+    pub new_width: u16,
+    pub new_height: u16,
+    pub input_buffer: &'i str,
+    pub awaiting_length_input: bool,
+    pub resize_mode: ResizeMode,
+    pub moving: bool,
+    pub new_x: u16,
+    pub new_y: u16,
+    pub presets: &'i [Preset],
+    pub aspect_lock: Option<(u8, u8)>,
+    pub select_all: bool,
+    // the side being resized, both unless the user picked one
+    pub axis: Axis,
+    // the size was submitted and waits for a yes before it is applied
+    pub confirming: bool,
+    // a resize was sent and no update showed the new size yet
+    pub resizing: bool,
+    // columns and rows of the screen, 0 while unknown
+    pub screen: (usize, usize),
+}
+
+// the floating panes to list and where the user is in it
+pub struct PaneList<'l> {
+    // panes along with their index, already narrowed down by the filter
+    pub panes: Vec<(usize, PaneUi)>,
+    pub selected_pane: Option<&'l PaneUi>,
+    pub current_pane_index: Option<usize>,
+    pub pending_close: Option<&'l PaneUi>,
+    pub filter: &'l str,
+    pub filtering: bool,
+    // screen columns and rows to scale the minimap from, when it is shown
+    pub minimap: Option<(usize, usize)>,
+    // screen columns to scale the size bars from, when they are shown
+    pub size_bars: Option<usize>,
+    // name of the other session the panes come from
+    pub browsed_session: Option<&'l str>,
+    pub session_picker: Option<SessionPicker<'l>>,
+    pub area: ListArea,
+    // raw ids of the selected pane below its size
+    pub debug: bool,
+    // pane whose size the next pane picked in the list gets
+    pub copy_source: Option<&'l PaneUi>,
+    // only the panes of the active tab are listed
+    pub current_tab_only: bool,
+    // how many panes a bulk resize hits, 0 when it hits all of them
+    pub marked: usize,
+}
+
+// where the list is scrolled to and the room it has on screen
+#[derive(Clone, Copy)]
+pub struct ListArea {
+    // first list line drawn
+    pub offset: usize,
+    // rows the list may take
+    pub capacity: usize,
+    pub cols: usize,
+}
+
+// every known session, flagged if it is the current one, and the one under the cursor
+pub struct SessionPicker<'s> {
+    pub sessions: Vec<(&'s str, bool)>,
+    pub cursor: usize,
+}
+
+// help and feedback drawn around the main view
+pub struct Footer<'f> {
+    pub show_help: bool,
+    pub status_message: Option<&'f str>,
+    pub keys: &'f KeyBindings,
+    pub dry_run: bool,
+    pub on_exit: OnExit,
+}
 
 pub fn compose_ui(
     rows: usize,
     cols: usize,
     colors: Colors,
-    panes: Vec<PaneUi>,
-    selected_pane: Option<&PaneUi>,
-    current_pane_index: Option<usize>,
-    new_width: u8,
-    new_height: u8,
+    pane_list: PaneList,
+    size_input: SizeInput,
+    footer: Footer,
 ) {
     clear_screen();
-    if let Some(pane) = selected_pane {
-        header_resize(rows, cols, colors, pane.pane_id);
-        selected_pane_size(&pane, colors);
-        set_pane_size(new_width, new_height, colors);
-        resize_control(rows, cols, colors);
-    } else {
+    if rows <= COMPACT_MAX_ROWS {
+        compact_view(&pane_list, &size_input, &footer, colors);
+        io::stdout().flush().unwrap();
+        return;
+    }
+    if footer.show_help {
         header_man(rows, cols, colors);
-        listing_panes(rows, cols, colors, panes, selected_pane, current_pane_index);
-        pane_control(rows, cols, colors);
+        if pane_list.selected_pane.is_some() {
+            help_overlay(&resize_keys(footer.keys), colors);
+        } else {
+            help_overlay(&list_keys(footer.keys, footer.on_exit), colors);
+        }
+    } else if let Some(picker) = pane_list.session_picker {
+        header_man(rows, cols, colors);
+        session_list(&picker, colors);
+        help_bar(rows, cols, colors, &session_keys());
+    } else if let Some(pane) = pane_list.pending_close {
+        header_man(rows, cols, colors);
+        confirm_close(pane, colors);
+    } else if let Some(pane) = pane_list.selected_pane {
+        // the mode decides what the typed numbers do, so it heads the view
+        let mode = if size_input.moving {
+            "[MOVE]"
+        } else {
+            "[RESIZE]"
+        };
+        let header = if size_input.select_all && pane_list.marked > 0 {
+            format!("{mode} {} selected panes", pane_list.marked)
+        } else if size_input.select_all {
+            format!("{mode} All floating panes")
+        } else {
+            format!("{mode} Pane by index - {}", pane.pane_id)
+        };
+        header_resize(rows, cols, colors, &header);
+        selected_pane_size(&pane, &size_input, colors);
+        if pane_list.debug {
+            println!(
+                "{} pane_id {}, is_plugin {}, tab_id {}",
+                colors.bold("Debug:"),
+                colors.text(&pane.pane_id.to_string()),
+                colors.text(&pane.is_plugin.to_string()),
+                colors.text(&pane.parent_tab.tab_id.to_string()),
+            );
+        }
+        set_pane_size(&size_input, colors);
+        if size_input.confirming {
+            confirm_resize(&size_input, colors);
+        }
+        preset_list(size_input.presets, colors);
+        if let Some(screen) = pane_list.minimap {
+            let selected = pane_list
+                .panes
+                .iter()
+                .find(|(_, listed)| listed.id() == pane.id())
+                .map(|(index, _)| *index);
+            minimap(&pane_list.panes, screen, selected, colors);
+        }
+        resize_control(rows, cols, colors, footer.keys);
+    } else {
+        if pane_list.current_tab_only {
+            header_resize(rows, cols, colors, "Floating Pane Manager - current tab");
+        } else {
+            header_man(rows, cols, colors);
+        }
+        if let Some(session) = pane_list.browsed_session {
+            println!(
+                "{} {} (browse only, <Ctrl+F> switches to it)",
+                colors.bold("Session:"),
+                colors.orange(session)
+            );
+        }
+        if let Some(source) = pane_list.copy_source {
+            println!(
+                "{} {} {}x{} (<ENTER> gives it to the highlighted pane, <ESC> cancels)",
+                colors.bold("Copying size of:"),
+                colors.orange(&source.name),
+                source.pane_columns,
+                source.pane_rows
+            );
+        }
+        filter_line(pane_list.filter, pane_list.filtering, colors);
+        if let Some(screen) = pane_list.minimap {
+            minimap(
+                &pane_list.panes,
+                screen,
+                pane_list.current_pane_index,
+                colors,
+            );
+        }
+        if pane_list.panes.is_empty() {
+            if pane_list.filter.is_empty() {
+                let scope = if pane_list.current_tab_only {
+                    "tab"
+                } else {
+                    "session"
+                };
+                println!("No floating panes in this {scope}. Create one with Alt-f.");
+            } else {
+                println!("No panes match the filter, <ESC> clears it.");
+            }
+        }
+        let position = list_position(
+            &pane_list.panes,
+            pane_list.current_pane_index,
+            pane_list.marked,
+        );
+        listing_panes(
+            rows,
+            colors,
+            pane_list.panes,
+            pane_list.selected_pane,
+            pane_list.current_pane_index,
+            pane_list.area,
+            pane_list.size_bars,
+        );
+        pane_control(rows, cols, colors, footer.keys, footer.on_exit);
+        if let Some(position) = position {
+            let col = cols.saturating_sub(position.len()).max(1);
+            print!(
+                "\x1b[{};{col}H{}",
+                rows.saturating_sub(2),
+                colors.text(&position)
+            );
+        }
+    }
+    // a dry run is too easy to forget about, so it keeps the status line when nothing else needs it
+    let dry_run_notice = "Dry run is on, nothing gets resized (<D> turns it off)";
+    if let Some(message) = footer
+        .status_message
+        .or(footer.dry_run.then_some(dry_run_notice))
+    {
+        status_line(rows, message, colors);
     }
     io::stdout().flush().unwrap();
 }
 
+// the pane under the cursor, or the one being resized, with the prompt or the status below
+fn compact_view(pane_list: &PaneList, size_input: &SizeInput, footer: &Footer, colors: Colors) {
+    if let Some(pane) = pane_list.pending_close {
+        confirm_close(pane, colors);
+        return;
+    }
+    if size_input.confirming {
+        confirm_resize(size_input, colors);
+        return;
+    }
+    let hint_line = if let Some(pane) = pane_list.selected_pane {
+        let size = colors.orange(&format!("{}x{}", pane.pane_columns, pane.pane_rows));
+        println!("{} {} {size}", colors.bold("Resize"), pane.name);
+        let asked = match (size_input.moving, size_input.awaiting_length_input) {
+            (true, false) => "x",
+            (true, true) => "y",
+            (false, false) => size_input.axis.name().unwrap_or("width"),
+            (false, true) => "length",
+        };
+        let unit = if size_input.moving {
+            "cells"
+        } else {
+            size_input.resize_mode.unit()
+        };
+        let typing = colors.cyan(&format!("{}_", size_input.input_buffer));
+        format!("Enter new {asked} -> [{typing}] {unit}")
+    } else {
+        let current = pane_list
+            .panes
+            .iter()
+            .enumerate()
+            .find(|(_, (index, _))| Some(*index) == pane_list.current_pane_index);
+        match current {
+            Some((position, (index, pane))) => println!(
+                "{} {}: {} {}",
+                colors.cursor(">"),
+                colors.text(&index.to_string()),
+                pane.name,
+                colors.text(&format!("({} / {})", position + 1, pane_list.panes.len()))
+            ),
+            None if pane_list.panes.is_empty() => println!("No floating panes"),
+            None => println!("{} panes, none highlighted", pane_list.panes.len()),
+        }
+        [
+            hint("<↓↑>", "Cycle"),
+            hint("<ENTER>", "Select"),
+            hint("<ESC>", "Leave"),
+        ]
+        .iter()
+        .map(|hint| format_hint(hint, colors))
+        .collect::<Vec<_>>()
+        .join("; ")
+    };
+    match footer.status_message {
+        Some(message) => println!("{}", colors.orange(message)),
+        None => println!("{hint_line}"),
+    }
+}
+
+// row of the first pane in the list, has to follow the layout `compose_ui` prints
+pub fn list_first_row(
+    session_shown: bool,
+    copy_shown: bool,
+    filter_shown: bool,
+    minimap_shown: bool,
+) -> usize {
+    let minimap_rows = if minimap_shown { MINIMAP_ROWS + 2 } else { 0 };
+    HEADER_ROWS
+        + usize::from(session_shown)
+        + usize::from(copy_shown)
+        + usize::from(filter_shown)
+        + minimap_rows
+}
+
+// rows left for the pane list between its first row and the footer
+pub fn list_capacity(rows: usize, first_row: usize) -> usize {
+    rows.saturating_sub(first_row + FOOTER_ROWS)
+}
+
 pub fn header_man(rows: usize, cols: usize, color: Colors) {
-    let text = color.cyan("Floating Pane Manager");
+    let text = color.border("Floating Pane Manager");
     let text_length = text.len();
 
-    let padding_each_side = (cols - text_length) / 2;
+    let padding_each_side = cols.saturating_sub(text_length) / 2;
     let repeated = " ".repeat(padding_each_side);
 
     print!("{}", repeated);
@@ -41,16 +332,15 @@ pub fn header_man(rows: usize, cols: usize, color: Colors) {
         print!(" ");
     }
 
-    let split = "-".repeat(cols - 1);
-    println!("{}", split);
+    let split = "-".repeat(cols.saturating_sub(1));
+    println!("{}", color.border(&split));
 }
 
-pub fn header_resize(rows: usize, cols: usize, color: Colors, pane_id: u32) {
-    let header = format!("Resize: Pane by index - {}", pane_id);
-    let head = color.cyan(&header);
+pub fn header_resize(rows: usize, cols: usize, color: Colors, header: &str) {
+    let head = color.border(header);
     let text_length = head.len();
 
-    let padding_each_side = (cols - text_length) / 2;
+    let padding_each_side = cols.saturating_sub(text_length) / 2;
     let repeated = " ".repeat(padding_each_side);
 
     print!("{}", repeated);
@@ -60,86 +350,593 @@ pub fn header_resize(rows: usize, cols: usize, color: Colors, pane_id: u32) {
         print!(" ");
     }
 
-    let split = "-".repeat(cols - 1);
-    println!("{}", split);
+    let split = "-".repeat(cols.saturating_sub(1));
+    println!("{}", color.border(&split));
+}
+
+fn filter_line(filter: &str, filtering: bool, colors: Colors) {
+    if filtering {
+        println!(
+            "{} /{}",
+            colors.bold("Filter:"),
+            colors.cyan(&format!("{filter}_"))
+        );
+    } else if !filter.is_empty() {
+        println!("{} /{}", colors.bold("Filter:"), colors.text(filter));
+    }
+}
+
+fn session_list(picker: &SessionPicker, colors: Colors) {
+    for (position, (name, is_current)) in picker.sessions.iter().enumerate() {
+        let cursor = if position == picker.cursor { ">" } else { " " };
+        let current = if *is_current { " (current)" } else { "" };
+        println!("{}{}{current}", colors.cursor(cursor), colors.bold(name));
+    }
 }
 
-fn selected_pane_size(pane: &PaneUi, colors: Colors) {
-    let width = colors.orange(&pane.pane_rows.to_string());
-    let height = colors.orange(&pane.pane_columns.to_string());
+fn confirm_close(pane: &PaneUi, colors: Colors) {
+    let name = colors.orange(&pane.name);
+    println!("Close pane {name} (ID: {})?", pane.pane_id);
+    println!(
+        "Press {} again to close it, any other key keeps it",
+        colors.text("<Ctrl+D>")
+    );
+}
+
+fn confirm_resize(size_input: &SizeInput, colors: Colors) {
+    let size = colors.orange(&format!(
+        "{}x{}",
+        size_input.new_width, size_input.new_height
+    ));
+    println!("Apply {size} {}?", size_input.resize_mode.unit());
+    println!(
+        "{} : {}; any other key : {}",
+        colors.text("<y>"),
+        colors.bold("Apply"),
+        colors.bold("Cancel")
+    );
+}
+
+fn selected_pane_size(pane: &PaneUi, size_input: &SizeInput, colors: Colors) {
+    let (screen_cols, screen_rows) = size_input.screen;
+    let width = format!(
+        "{} cols{}",
+        colors.orange(&pane.pane_columns.to_string()),
+        in_other_unit(pane.pane_columns, ResizeMode::Cells, screen_cols, "cols")
+    );
+    let height = format!(
+        "{} rows{}",
+        colors.orange(&pane.pane_rows.to_string()),
+        in_other_unit(pane.pane_rows, ResizeMode::Cells, screen_rows, "rows")
+    );
 
     let cell_horizontal_border = "".repeat(32); // Adjust the length as needed
     let cell_vertical_border = "|";
 
     println!("{}", cell_horizontal_border);
+    let resizing = if size_input.resizing {
+        colors.cyan(" resizing…")
+    } else {
+        String::new()
+    };
     println!(
-        "{} Width: {} | Length: {} {}",
-        cell_vertical_border, width, height, cell_vertical_border
+        "{} Width: {} | Length: {} {}{}",
+        cell_vertical_border, width, height, cell_vertical_border, resizing
     );
     println!("{}", cell_horizontal_border);
 }
 
-fn set_pane_size(new_width: u8, new_height: u8, colors: Colors) {
-    let new_width = colors.magenta(&new_width.to_string());
-    let new_height = colors.magenta(&new_height.to_string());
-    let width_text = colors.bold("Enter new width");
-    let height_text = colors.bold("Enter new length");
-    println!("- {width_text}  -> [{new_width}] percent");
-    println!("- {height_text} -> [{new_height}] percent");
+fn set_pane_size(size_input: &SizeInput, colors: Colors) {
+    let (first, second, first_text, second_text, unit) = if size_input.moving {
+        (
+            size_input.new_x,
+            size_input.new_y,
+            "Enter new x     ",
+            "Enter new y     ",
+            "cells",
+        )
+    } else {
+        (
+            size_input.new_width,
+            size_input.new_height,
+            "Enter new width ",
+            "Enter new length",
+            size_input.resize_mode.unit(),
+        )
+    };
+    let typing = colors.cyan(&format!("{}_", size_input.input_buffer));
+    if let (false, Some(axis)) = (size_input.moving, size_input.axis.name()) {
+        let prompt = colors.bold(&format!("Enter new {axis:<6}"));
+        println!("- {prompt} -> [{typing}] {unit}");
+        println!(
+            "- {} -> {axis} only, the other side keeps its size",
+            colors.bold("Axis            ")
+        );
+        return;
+    }
+    // sizes already entered also show in the unit they aren't entered in
+    let (screen_cols, screen_rows) = size_input.screen;
+    let entered = |value: u16, screen_side: usize, cells_name: &str| {
+        let converted = if size_input.moving {
+            String::new()
+        } else {
+            in_other_unit(
+                usize::from(value),
+                size_input.resize_mode,
+                screen_side,
+                cells_name,
+            )
+        };
+        format!("{}{converted}", colors.text(&value.to_string()))
+    };
+    let (first, second, asked) = match (size_input.awaiting_length_input, size_input.moving) {
+        (true, true) => (entered(first, screen_cols, "cols"), typing, "Y"),
+        (true, false) => (entered(first, screen_cols, "cols"), typing, "LENGTH"),
+        (false, true) => (typing, entered(second, screen_rows, "rows"), "X"),
+        (false, false) => (typing, entered(second, screen_rows, "rows"), "WIDTH"),
+    };
+    // spelled out since the two rows below look alike once a value got reset
+    if !size_input.confirming {
+        println!("{}", colors.cyan(&format!("Enter {asked}:")));
+    }
+    let first_text = colors.bold(first_text);
+    let second_text = colors.bold(second_text);
+    println!("- {first_text} -> [{first}] {unit}");
+    println!("- {second_text} -> [{second}] {unit}");
+    if let Some((width, height)) = size_input.aspect_lock {
+        let ratio = colors.text(&format!("{width}:{height}"));
+        println!(
+            "- {} -> [{ratio}] length follows width",
+            colors.bold("Aspect lock")
+        );
+    }
+}
+
+// the value in the unit it isn't given in, e.g. " (96 cols)" for 50 percent of 192 columns,
+// nothing while the screen size is unknown or nothing was entered
+fn in_other_unit(value: usize, mode: ResizeMode, screen_side: usize, cells_name: &str) -> String {
+    if value == 0 || screen_side == 0 {
+        return String::new();
+    }
+    match mode {
+        ResizeMode::Percent => format!(" ({} {cells_name})", value * screen_side / 100),
+        ResizeMode::Cells => format!(" ({}%)", value * 100 / screen_side),
+    }
 }
 
-pub fn pane_control(row: usize, max_cols: usize, colors: Colors) {
-    let arrows = colors.magenta("<↓↑>");
-    let navigate = colors.bold("Navigate");
-    let enter = colors.magenta("<ENTER>");
-    let select = colors.bold("Select a pane");
-    let esc = colors.magenta("<ESC>");
-    let hide = colors.bold("Hide this plugin");
-    let exit = colors.magenta("<Ctrl+E>");
-    let close = colors.bold("Close this pane");
+fn preset_list(presets: &[Preset], colors: Colors) {
+    if presets.is_empty() {
+        return;
+    }
+    let presets: Vec<String> = presets
+        .iter()
+        .enumerate()
+        .map(|(idx, preset)| {
+            let key = colors.text(&format!("<F{}>", idx + 1));
+            format!("{key} {} {}x{}", preset.name, preset.width, preset.height)
+        })
+        .collect();
+    println!("- {} -> {}", colors.bold("Presets"), presets.join("; "));
+}
 
-    let split = "─".repeat(max_cols);
-    let split_ln_no = row - 1;
-    // ANSI escape code to draw a line at the second last line, \x1b[{}H sets the cursor to second last line
-    println!("\x1b[{}H{}", split_ln_no, split);
-    print!("\u{1b}[m\u{1b}[{row}H{arrows} : {navigate}; {enter} : {select}; {esc} : {hide}; {exit} : {close}");
-}
-
-pub fn resize_control(row: usize, max_cols: usize, colors: Colors) {
-    let numbers = colors.magenta("<0-99>");
-    let size = colors.bold("Set size");
-    let enter = colors.magenta("<ENTER>");
-    let confirm = colors.bold("Confirm a size");
-    let select = colors.magenta("<Ctrl+S>");
-    let submit = colors.bold("Submit");
-    let reset = colors.magenta("<Ctrl+R>");
-    let reset_size = colors.bold("Reset size");
-    let esc = colors.magenta("<ESC>");
-    let cancel = colors.bold("Cancel");
-    let exit = colors.magenta("<Ctrl+E>");
-    let close = colors.bold("Close this pane");
+// scaled-down screen with each floating pane drawn as the last digit of its index
+fn minimap(
+    panes: &[(usize, PaneUi)],
+    (screen_cols, screen_rows): (usize, usize),
+    emphasized: Option<usize>,
+    colors: Colors,
+) {
+    let mut grid = vec![vec![None; MINIMAP_COLS]; MINIMAP_ROWS];
+    // the emphasized pane goes last so it stays on top of any overlap
+    let (top_most, rest): (Vec<_>, Vec<_>) = panes
+        .iter()
+        .partition(|(index, _)| Some(*index) == emphasized);
+    for (index, pane) in rest.into_iter().chain(top_most) {
+        let left = pane.pane_x * MINIMAP_COLS / screen_cols;
+        let top = pane.pane_y * MINIMAP_ROWS / screen_rows;
+        let right = ((pane.pane_x + pane.pane_columns) * MINIMAP_COLS)
+            .div_ceil(screen_cols)
+            .max(left + 1)
+            .min(MINIMAP_COLS);
+        let bottom = ((pane.pane_y + pane.pane_rows) * MINIMAP_ROWS)
+            .div_ceil(screen_rows)
+            .max(top + 1)
+            .min(MINIMAP_ROWS);
+        for line in grid.iter_mut().take(bottom).skip(top) {
+            for cell in line.iter_mut().take(right).skip(left) {
+                *cell = Some(*index);
+            }
+        }
+    }
+
+    let edge = colors.border(&format!("+{}+", "-".repeat(MINIMAP_COLS)));
+    println!("{edge}");
+    for line in grid {
+        let cells: String = line
+            .iter()
+            .map(|cell| match cell {
+                Some(index) if Some(*index) == emphasized => colors.cursor("#"),
+                Some(index) => colors.text(&(index % 10).to_string()),
+                None => " ".to_string(),
+            })
+            .collect();
+        println!("{}{cells}{}", colors.border("|"), colors.border("|"));
+    }
+    println!("{edge}");
+}
+
+type Hint = (String, &'static str);
+
+fn hint(key: &str, action: &'static str) -> Hint {
+    (key.to_string(), action)
+}
+
+fn key_label(key: &Key) -> String {
+    match key {
+        Key::Ctrl(c) => format!("<Ctrl+{}>", c.to_ascii_uppercase()),
+        key => format!("<{key}>"),
+    }
+}
+
+fn list_keys(keys: &KeyBindings, on_exit: OnExit) -> Vec<Hint> {
+    let exit = match on_exit {
+        OnExit::Hide => "Hide this plugin",
+        OnExit::Close => "Close this plugin",
+    };
+    vec![
+        hint("<↓↑/jk>", "Navigate"),
+        hint("<Tab/Shift+Tab>", "Next/Previous"),
+        hint("<←→>", "Next/Previous column"),
+        hint("<PgDn/PgUp>", "Page"),
+        hint("<Home/End>", "First/Last"),
+        hint("<Alt+0-9>", "Jump to index"),
+        hint("<ENTER>", "Select a pane"),
+        hint("</>", "Filter"),
+        hint("<s>", "Sort by index/title/size"),
+        hint("<p>", "Pin to the top"),
+        hint("<SPACE>", "Select for Ctrl+A"),
+        hint("<u>", "Clear the selection"),
+        hint("<E>", "Arrange in a grid"),
+        hint("<Ctrl+Z>", "Undo the arrangement"),
+        hint("<m>", "Minimap"),
+        hint("<b>", "Size bars"),
+        hint("<t>", "Show tiled panes"),
+        hint("<T>", "Current tab/All tabs"),
+        hint("<S>", "Sessions"),
+        hint("<ESC>", exit),
+        hint("<Ctrl+F>", "Focus"),
+        hint("<Ctrl+A>", "Select all"),
+        hint("<D>", "Dry run"),
+        hint("<A>", "Auto hide"),
+        hint("<N>", "Next pane after a resize"),
+        hint("<Ctrl+D> twice", "Close highlighted"),
+        (key_label(&keys.close), "Close this pane"),
+    ]
+}
+
+fn session_keys() -> Vec<Hint> {
+    vec![
+        hint("<↓↑/jk>", "Navigate"),
+        hint("<ENTER>", "Browse session"),
+        hint("<ESC>", "Back"),
+    ]
+}
+
+fn resize_keys(keys: &KeyBindings) -> Vec<Hint> {
+    vec![
+        hint("<0-99>", "Set size"),
+        hint("<+/->", "Relative size"),
+        hint("<ENTER>", "Confirm a size"),
+        hint("<Alt+ENTER>", "Both sides at once"),
+        (key_label(&keys.resize), "Submit"),
+        hint("<ESC>", "Cancel"),
+        (key_label(&keys.back), "Back to the list"),
+        hint("<←↓↑→/hjkl>", "Nudge size"),
+        hint("<Alt/HJKL>", "Fine/Coarse nudge"),
+        hint("<BACKSPACE>", "Erase a digit"),
+        hint("<F1-F12>", "Apply a preset"),
+        hint("<m>", "Minimap"),
+        hint("<i>", "Pane ids"),
+        hint("<c>", "Copy size to another pane"),
+        hint("<Ctrl+P>", "Percent/Cells"),
+        hint("<Ctrl+W>", "Resize/Move"),
+        hint("<W/V>", "Width/Length only"),
+        hint("<Ctrl+C>", "Center"),
+        hint("<Ctrl+X>", "Maximize/Restore"),
+        hint("<Ctrl+T>", "Swap width/length"),
+        hint("<g>", "Golden ratio"),
+        hint("<f>", "Fit 80 columns"),
+        hint("<=>", "Half the screen"),
+        hint("<R>", "Remember size for title"),
+        hint("<Ctrl+B>", "Embed into layout"),
+        hint("<Ctrl+L>", "Aspect lock"),
+        hint("<Ctrl+Z>", "Undo"),
+        (key_label(&keys.reset), "Reset size"),
+        hint("<r>", "Restore defaults"),
+        hint("<Ctrl+F>", "Focus"),
+        (key_label(&keys.close), "Close this pane"),
+    ]
+}
+
+pub fn pane_control(
+    row: usize,
+    max_cols: usize,
+    colors: Colors,
+    keys: &KeyBindings,
+    on_exit: OnExit,
+) {
+    help_bar(row, max_cols, colors, &list_keys(keys, on_exit));
+}
+
+pub fn resize_control(row: usize, max_cols: usize, colors: Colors, keys: &KeyBindings) {
+    help_bar(row, max_cols, colors, &resize_keys(keys));
+}
+
+// as many hints as fit on the last line, the rest is listed by the help overlay
+fn help_bar(row: usize, max_cols: usize, colors: Colors, keys: &[Hint]) {
+    let help_key = hint("<?>", "Help");
+    let hint_len = |(key, action): &Hint| key.chars().count() + action.chars().count() + 5;
+    let mut room = max_cols.saturating_sub(hint_len(&help_key));
+    let mut hints = Vec::new();
+    for hint in keys {
+        if hint_len(hint) > room {
+            break;
+        }
+        room -= hint_len(hint);
+        hints.push(format_hint(hint, colors));
+    }
+    hints.push(format_hint(&help_key, colors));
 
     let split = "─".repeat(max_cols);
     let split_ln_no = row - 1;
     // ANSI escape code to draw a line at the second last line, \x1b[{}H sets the cursor to second last line
     println!("\x1b[{}H{}", split_ln_no, split);
-    print!("\u{1b}[m\u{1b}[{row}H{numbers} : {size}; {enter} : {confirm}; {select} : {submit}; {reset} : {reset_size};  {esc} : {cancel}; {exit} : {close}");
+    print!("\u{1b}[m\u{1b}[{row}H{}", hints.join("; "));
+}
+
+// where the cursor is in the list, like `3 / 12`, drawn at the right end of the status line
+// the number of marked panes goes first so it is on screen whatever the cursor is on
+fn list_position(
+    panes: &[(usize, PaneUi)],
+    current_pane_index: Option<usize>,
+    marked: usize,
+) -> Option<String> {
+    let marked = (marked > 0).then(|| format!("{marked} selected"));
+    let position = panes
+        .iter()
+        .position(|(index, _)| Some(*index) == current_pane_index)
+        .map(|position| format!("{} / {}", position + 1, panes.len()));
+    match (marked, position) {
+        (Some(marked), Some(position)) => Some(format!("{marked}, {position}")),
+        (marked, position) => marked.or(position),
+    }
+}
+
+// right above the separator of the help bar
+fn status_line(row: usize, message: &str, colors: Colors) {
+    print!("\x1b[{}H{}", row.saturating_sub(2), colors.orange(message));
+}
+
+fn format_hint((key, action): &Hint, colors: Colors) -> String {
+    format!("{} : {}", colors.text(key), colors.bold(action))
+}
+
+fn help_overlay(keys: &[Hint], colors: Colors) {
+    for (key, action) in keys {
+        println!("  {:<14} {}", colors.text(key), colors.bold(action));
+    }
+    println!();
+    println!("Press any key to close this help");
+}
+
+// a line of the pane list, both pointing into the panes by position
+#[derive(Clone, Copy)]
+enum ListLine {
+    // header of the tab the pane at that position starts
+    Tab(usize),
+    Pane(usize),
+}
+
+fn list_lines(panes: &[(usize, PaneUi)]) -> Vec<ListLine> {
+    let mut current_tab = None;
+    let mut lines = Vec::new();
+    for (position, (_, pane)) in panes.iter().enumerate() {
+        if current_tab != Some(pane.parent_tab.tab_id) {
+            current_tab = Some(pane.parent_tab.tab_id);
+            lines.push(ListLine::Tab(position));
+        }
+        lines.push(ListLine::Pane(position));
+    }
+    lines
+}
+
+// lines shown at once when the list doesn't fit, a row above and below goes to the indicators
+fn scroll_window(capacity: usize) -> usize {
+    capacity.saturating_sub(2).max(1)
+}
+
+// how the list lines are spread over the screen, column after column, when a single one
+// doesn't fit; only once all columns are full does the list scroll
+#[derive(Clone, Copy)]
+struct ListLayout {
+    offset: usize,
+    // rows of each column
+    window: usize,
+    columns: usize,
+    scrolling: bool,
+}
+
+impl ListLayout {
+    fn new(line_count: usize, offset: usize, area: ListArea) -> Self {
+        let max_columns = (area.cols / LIST_COLUMN_MIN_WIDTH).max(1);
+        let capacity = area.capacity.max(1);
+        if line_count <= capacity * max_columns {
+            Self {
+                offset: 0,
+                window: line_count.min(capacity),
+                columns: line_count.div_ceil(capacity).max(1),
+                scrolling: false,
+            }
+        } else {
+            Self {
+                offset,
+                window: scroll_window(capacity),
+                columns: max_columns,
+                scrolling: true,
+            }
+        }
+    }
+
+    // lines on screen at once, over all columns
+    fn shown(&self) -> usize {
+        self.window * self.columns
+    }
+}
+
+// moves the window of visible lines as little as needed to keep the cursor in it
+pub fn scroll_offset(panes: &[(usize, PaneUi)], cursor: Option<usize>, area: ListArea) -> usize {
+    let lines = list_lines(panes);
+    let layout = ListLayout::new(lines.len(), area.offset, area);
+    if !layout.scrolling {
+        return 0;
+    }
+    let shown = layout.shown();
+    let mut offset = area.offset.min(lines.len().saturating_sub(shown));
+    if let Some(line) = cursor_line(&lines, panes, cursor) {
+        // bring the tab header along when scrolling up to the first pane of a tab
+        let top = match line.checked_sub(1).map(|above| lines[above]) {
+            Some(ListLine::Tab(_)) => line - 1,
+            _ => line,
+        };
+        if top < offset {
+            offset = top;
+        } else if line >= offset + shown {
+            offset = line + 1 - shown;
+        }
+    }
+    offset
+}
+
+fn cursor_line(
+    lines: &[ListLine],
+    panes: &[(usize, PaneUi)],
+    cursor: Option<usize>,
+) -> Option<usize> {
+    let idx = cursor?;
+    lines
+        .iter()
+        .position(|line| matches!(line, ListLine::Pane(position) if panes[*position].0 == idx))
+}
+
+// index of the pane drawn at the given list row and screen column, headers and indicators
+// map to nothing
+pub fn pane_at(panes: &[(usize, PaneUi)], row: usize, col: usize, area: ListArea) -> Option<usize> {
+    let lines = list_lines(panes);
+    let layout = ListLayout::new(lines.len(), area.offset, area);
+    let row = if layout.scrolling {
+        row.checked_sub(1)?
+    } else {
+        row
+    };
+    let column = col / (area.cols / layout.columns).max(1);
+    if row >= layout.window || column >= layout.columns {
+        return None;
+    }
+    match lines.get(layout.offset + column * layout.window + row)? {
+        ListLine::Pane(position) => Some(panes[*position].0),
+        ListLine::Tab(_) => None,
+    }
+}
+
+// index of the pane on the same row of the next or previous column, a tab header there gives
+// way to the pane it heads
+pub fn pane_beside(
+    panes: &[(usize, PaneUi)],
+    cursor: Option<usize>,
+    area: ListArea,
+    forward: bool,
+) -> Option<usize> {
+    let lines = list_lines(panes);
+    let layout = ListLayout::new(lines.len(), area.offset, area);
+    if layout.columns == 1 {
+        return None;
+    }
+    let line = cursor_line(&lines, panes, cursor)?;
+    let beside = if forward {
+        line + layout.window
+    } else {
+        line.checked_sub(layout.window)?
+    };
+    let (ListLine::Pane(position) | ListLine::Tab(position)) = *lines.get(beside)?;
+    Some(panes[position].0)
 }
 
 pub fn listing_panes(
     row: usize,
-    max_cols: usize,
     colors: Colors,
-    panes: Vec<PaneUi>,
+    panes: Vec<(usize, PaneUi)>,
     selected_pane: Option<&PaneUi>,
     current_pane_index: Option<usize>,
+    area: ListArea,
+    size_bars: Option<usize>,
 ) {
-    let mut index = 1;
-    for pane in panes {
-        let mut new_line = DrawPaneLine::new(pane, selected_pane, current_pane_index, colors);
-        new_line.draw(index);
-        println!("{}", new_line.line);
-        index += 1;
+    let lines = list_lines(&panes);
+    let layout = ListLayout::new(lines.len(), area.offset, area);
+    let column_width = area.cols / layout.columns;
+    // the bars and the blank before them come out of the room for the titles
+    let bar_room = if size_bars.is_some() {
+        SIZE_BAR_LEN + 1
+    } else {
+        0
+    };
+    let title_width = title_width(&panes, column_width.saturating_sub(bar_room));
+    let count_panes = |lines: &[ListLine]| {
+        lines
+            .iter()
+            .filter(|line| matches!(line, ListLine::Pane(_)))
+            .count()
+    };
+    let offset = layout.offset.min(lines.len());
+    let hidden_above = count_panes(&lines[..offset]);
+    let hidden_below = count_panes(lines.get(offset + layout.shown()..).unwrap_or_default());
+
+    if layout.scrolling {
+        if hidden_above > 0 {
+            println!("{}", colors.text(&format!("▲ {hidden_above} more")));
+        } else {
+            println!();
+        }
+    }
+    for list_row in 0..layout.window {
+        for column in 0..layout.columns {
+            let Some(line) = lines.get(offset + column * layout.window + list_row) else {
+                continue;
+            };
+            // every column starts at its own screen column, \x1b[{}G moves the cursor there
+            if layout.columns > 1 {
+                print!("\x1b[{}G", column * column_width + 1);
+            }
+            match *line {
+                ListLine::Tab(position) => {
+                    let tab = &panes[position].1.parent_tab;
+                    print!("{}", colors.bold(&format!("Tab: {}", tab.name)));
+                }
+                ListLine::Pane(position) => {
+                    let (index, pane) = &panes[position];
+                    let mut new_line =
+                        DrawPaneLine::new(pane.clone(), selected_pane, current_pane_index, colors);
+                    // a background filling the row would run into the other columns
+                    new_line.fill_row = layout.columns == 1;
+                    new_line.size_bar = size_bars;
+                    new_line.draw(*index, title_width);
+                    print!("{}", new_line.line);
+                }
+            }
+        }
+        println!();
+    }
+    if hidden_below > 0 {
+        println!("{}", colors.text(&format!("▼ {hidden_below} more")));
     }
 }
 